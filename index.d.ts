@@ -8,18 +8,59 @@ export interface CsiMethod {
   dst?: string
   operator?: boolean
   allowedWithoutCallee?: boolean
+  kind?: 'after' | 'before' | 'around' | 'constructor' | 'propertyRead'
+  receiver?: string
+  receiverPattern?: string
+}
+export interface ParserOptions {
+  decorators?: boolean
+  decoratorsBeforeExport?: boolean
+  isModule?: 'auto' | 'module' | 'script' | 'commonjs'
+  target?: string
 }
 export interface RewriterConfig {
   chainSourceMap?: boolean
   comments?: boolean
   localVarPrefix?: string
   csiMethods?: Array<CsiMethod>
+  telemetryVerbosity?: string
+  literals?: boolean
+  syntax?: 'auto' | 'ecmascript' | 'typescript'
+  stripTypes?: boolean
+  jsx?: boolean
+  parserOptions?: ParserOptions
+  callSiteLocations?: boolean
+  siteIds?: boolean
+  lenient?: boolean
+}
+export interface RewriteOptions {
+  csiMethods?: Array<string>
   literals?: boolean
+  chainSourceMap?: boolean
+  telemetryVerbosity?: string
+  parserOptions?: ParserOptions
 }
 export interface Result {
   content: string
   metrics?: Metrics
   literalsResult?: LiteralsResult
+  locationFiles?: Array<string>
+  sites?: Array<SiteInfo>
+}
+export interface SiteInfo {
+  id: number
+  kind: 'operator' | 'template' | 'taggedTemplate' | 'propertyWrite' | 'method' | 'constructor' | 'propertyRead'
+  tag: string
+  line?: number
+  column?: number
+  function?: string
+}
+export interface RewriteError extends Error {
+  code: 'ParseError' | 'SourceMapError' | 'Panic'
+  file: string
+  line?: number
+  column?: number
+  diagnostic: string
 }
 export interface Metrics {
   status: string
@@ -42,7 +83,7 @@ export interface LiteralInfo {
 }
 export class NonCacheRewriter {
   constructor(config?: RewriterConfig | undefined | null)
-  rewrite(code: string, file: string): Result
+  rewrite(code: string, file: string, options?: RewriteOptions | undefined | null): Result
   csiMethods(): Array<string>
 }
 
@@ -20,18 +20,26 @@ class DummyRewriter {
 
 let NativeRewriter
 
+// logger and logLevel are handled by setLogger and are not part of the native rewriter config
+function getNativeConfig (config) {
+  if (!config) return config
+
+  const { logger, logLevel, ...nativeConfig } = config
+  return nativeConfig
+}
+
 class NonCacheRewriter {
   constructor (config) {
     if (NativeRewriter) {
-      this.nativeRewriter = new NativeRewriter(config)
+      this.nativeRewriter = new NativeRewriter(getNativeConfig(config))
       this.setLogger(config)
     } else {
       this.nativeRewriter = new DummyRewriter()
     }
   }
 
-  rewrite (code, file) {
-    const response = this.nativeRewriter.rewrite(code, file)
+  rewrite (code, file, options) {
+    const response = this.nativeRewriter.rewrite(code, file, options)
 
     // rewrite returns an empty content when for the 'notmodified' status
     if (response?.metrics?.status === 'notmodified') {
@@ -63,8 +71,8 @@ class NonCacheRewriter {
 }
 
 class CacheRewriter extends NonCacheRewriter {
-  rewrite (code, file) {
-    const response = super.rewrite(code, file)
+  rewrite (code, file, options) {
+    const response = super.rewrite(code, file, options)
 
     try {
       const { metrics, content } = response
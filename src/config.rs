@@ -0,0 +1,614 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use crate::{
+    rewriter::{generate_prefix_stmts, Config, ModuleKind, ParserOptions, SyntaxMode},
+    telemetry::TelemetryVerbosity,
+    util::rnd_string,
+    visitor::{
+        csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
+        visitor_util::{
+            DD_PLUS_OPERATOR, DD_PROPERTY_WRITE_OPERATOR, DD_TAGGED_TEMPLATE_OPERATOR,
+            DD_TEMPLATE_LITERAL_OPERATOR,
+        },
+    },
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, fmt};
+
+const OPERATORS: [&str; 4] = [
+    DD_PLUS_OPERATOR,
+    DD_TEMPLATE_LITERAL_OPERATOR,
+    DD_PROPERTY_WRITE_OPERATOR,
+    DD_TAGGED_TEMPLATE_OPERATOR,
+];
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CsiMethodConfig {
+    pub src: String,
+    pub dst: Option<String>,
+    pub operator: Option<bool>,
+    pub allowed_without_callee: Option<bool>,
+    pub kind: Option<String>,
+    pub receiver: Option<String>,
+    pub receiver_pattern: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ParserOptionsConfig {
+    pub decorators: Option<bool>,
+    pub decorators_before_export: Option<bool>,
+    pub is_module: Option<String>,
+    pub target: Option<String>,
+}
+
+impl ParserOptionsConfig {
+    /// Overrides the `base` parser options with the configured ones
+    pub fn to_parser_options(&self, base: &ParserOptions) -> ParserOptions {
+        ParserOptions {
+            decorators: self.decorators.unwrap_or(base.decorators),
+            decorators_before_export: self
+                .decorators_before_export
+                .unwrap_or(base.decorators_before_export),
+            is_module: self
+                .is_module
+                .as_deref()
+                .and_then(ModuleKind::try_parse)
+                .unwrap_or(base.is_module),
+            target: self
+                .target
+                .as_deref()
+                .and_then(ParserOptions::try_parse_target)
+                .unwrap_or(base.target),
+        }
+    }
+}
+
+/// Binding agnostic rewriter configuration.
+/// Both wasm and napi bindings deserialize the js config object into this struct and
+/// build the rewriter `Config` from it.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RewriterConfig {
+    pub chain_source_map: Option<bool>,
+    pub comments: Option<bool>,
+    pub local_var_prefix: Option<String>,
+    pub csi_methods: Option<Vec<CsiMethodConfig>>,
+    pub telemetry_verbosity: Option<String>,
+    pub literals: Option<bool>,
+    pub syntax: Option<String>,
+    pub strip_types: Option<bool>,
+    pub jsx: Option<bool>,
+    pub parser_options: Option<ParserOptionsConfig>,
+    pub call_site_locations: Option<bool>,
+    pub site_ids: Option<bool>,
+    pub lenient: Option<bool>,
+}
+
+impl RewriterConfig {
+    /// Builds the config from the js config object reporting every invalid key.
+    /// With `lenient: true` the config is not validated and an invalid config falls back to the
+    /// default one.
+    pub fn from_value(value: Value) -> Result<Self, ConfigErrors> {
+        let lenient = value
+            .get("lenient")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if lenient {
+            return Ok(serde_json::from_value::<RewriterConfig>(value).unwrap_or_default());
+        }
+
+        let mut validator = ConfigValidator::default();
+        validator.validate(&value);
+        if !validator.errors.is_empty() {
+            return Err(ConfigErrors {
+                errors: validator.errors,
+                subject: CONFIG_SUBJECT,
+            });
+        }
+
+        if value.is_null() {
+            return Ok(RewriterConfig::default());
+        }
+
+        serde_json::from_value::<RewriterConfig>(value)
+            .map_err(|err| ConfigErrors::new("", &err.to_string()))
+    }
+
+    pub fn get_csi_methods(&self) -> CsiMethods {
+        match &self.csi_methods {
+            Some(methods) => CsiMethods::new(
+                &methods
+                    .iter()
+                    .map(|m| {
+                        CsiMethod::new(
+                            m.src.clone(),
+                            m.dst.clone(),
+                            m.operator.unwrap_or(false),
+                            m.allowed_without_callee.unwrap_or(false),
+                            m.kind
+                                .as_deref()
+                                .and_then(CsiMethodKind::try_parse)
+                                .unwrap_or_default(),
+                            m.receiver.clone(),
+                        )
+                        .with_receiver_pattern(m.receiver_pattern.clone())
+                    })
+                    .collect::<Vec<CsiMethod>>(),
+            ),
+
+            None => CsiMethods::empty(),
+        }
+    }
+
+    pub fn to_config(&self) -> Config {
+        let csi_methods = self.get_csi_methods();
+        let call_site_locations = self.call_site_locations.unwrap_or(false);
+        let site_ids = self.site_ids.unwrap_or(false);
+        let file_prefix_code = generate_prefix_stmts(&csi_methods, call_site_locations, site_ids);
+
+        Config {
+            chain_source_map: self.chain_source_map.unwrap_or(false),
+            print_comments: self.comments.unwrap_or(false),
+            local_var_prefix: self
+                .local_var_prefix
+                .clone()
+                .unwrap_or_else(|| rnd_string(6)),
+            csi_methods,
+            verbosity: TelemetryVerbosity::parse(self.telemetry_verbosity.clone()),
+            literals: self.literals.unwrap_or(true),
+            file_prefix_code,
+            syntax: SyntaxMode::parse(self.syntax.clone()),
+            strip_types: self.strip_types.unwrap_or(false),
+            jsx: self.jsx.unwrap_or(false),
+            parser_options: self
+                .parser_options
+                .as_ref()
+                .map(|options| options.to_parser_options(&ParserOptions::default()))
+                .unwrap_or_default(),
+            call_site_locations,
+            site_ids,
+        }
+    }
+}
+
+/// Options of a single `rewrite()` call, merged over the rewriter `Config`.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RewriteOptions {
+    pub csi_methods: Option<Vec<String>>,
+    pub literals: Option<bool>,
+    pub chain_source_map: Option<bool>,
+    pub telemetry_verbosity: Option<String>,
+    pub parser_options: Option<ParserOptionsConfig>,
+}
+
+impl RewriteOptions {
+    /// Builds the options from the js options object reporting every invalid key.
+    /// Unlike the config, options are always validated. `csiMethods` must be a subset of the
+    /// `config` csi methods `src`.
+    pub fn from_value(value: Value, config: &Config) -> Result<Self, ConfigErrors> {
+        let mut validator = ConfigValidator::default();
+        validator.validate_options(&value, &config.csi_methods);
+        if !validator.errors.is_empty() {
+            return Err(ConfigErrors {
+                errors: validator.errors,
+                subject: OPTIONS_SUBJECT,
+            });
+        }
+
+        if value.is_null() {
+            return Ok(RewriteOptions::default());
+        }
+
+        serde_json::from_value::<RewriteOptions>(value).map_err(|err| ConfigErrors {
+            subject: OPTIONS_SUBJECT,
+            ..ConfigErrors::new("", &err.to_string())
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.csi_methods.is_none()
+            && self.literals.is_none()
+            && self.chain_source_map.is_none()
+            && self.telemetry_verbosity.is_none()
+            && self.parser_options.is_none()
+    }
+
+    /// Returns the `config` used to rewrite a single file, the base one if there is nothing to
+    /// override.
+    pub fn apply<'a>(&self, config: &'a Config) -> Cow<'a, Config> {
+        if self.is_empty() {
+            return Cow::Borrowed(config);
+        }
+
+        let mut file_config = config.clone();
+        if let Some(csi_methods) = &self.csi_methods {
+            file_config.csi_methods = config.csi_methods.subset(csi_methods);
+        }
+        if let Some(literals) = self.literals {
+            file_config.literals = literals;
+        }
+        if let Some(chain_source_map) = self.chain_source_map {
+            file_config.chain_source_map = chain_source_map;
+        }
+        if let Some(verbosity) = &self.telemetry_verbosity {
+            file_config.verbosity = TelemetryVerbosity::parse(Some(verbosity.clone()));
+        }
+        if let Some(parser_options) = &self.parser_options {
+            file_config.parser_options = parser_options.to_parser_options(&config.parser_options);
+        }
+        Cow::Owned(file_config)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub key: String,
+    pub message: String,
+}
+
+const CONFIG_SUBJECT: &str = "rewriter config";
+const OPTIONS_SUBJECT: &str = "rewrite options";
+
+#[derive(Serialize, Debug)]
+pub struct ConfigErrors {
+    pub errors: Vec<ConfigError>,
+    #[serde(skip)]
+    subject: &'static str,
+}
+
+impl ConfigErrors {
+    pub fn new(key: &str, message: &str) -> Self {
+        ConfigErrors {
+            errors: vec![ConfigError {
+                key: key.to_string(),
+                message: message.to_string(),
+            }],
+            subject: CONFIG_SUBJECT,
+        }
+    }
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self
+            .errors
+            .iter()
+            .map(|error| match error.key.as_str() {
+                "" => error.message.clone(),
+                key => format!("{key}: {}", error.message),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "Invalid {}: {errors}", self.subject)
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+#[derive(Default)]
+struct ConfigValidator {
+    errors: Vec<ConfigError>,
+}
+
+impl ConfigValidator {
+    fn error(&mut self, key: &str, message: &str) {
+        self.errors.push(ConfigError {
+            key: key.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn validate(&mut self, value: &Value) {
+        let config = match value {
+            Value::Null => return,
+            Value::Object(config) => config,
+            _ => return self.error("", "expected an object"),
+        };
+
+        for (key, value) in config.iter().filter(|(_, value)| !value.is_null()) {
+            match key.as_str() {
+                "chainSourceMap" | "comments" | "literals" | "stripTypes" | "jsx"
+                | "callSiteLocations" | "siteIds" | "lenient" => {
+                    self.expect_bool(key, value);
+                }
+                "localVarPrefix" => self.validate_local_var_prefix(key, value),
+                "telemetryVerbosity" => self.validate_telemetry_verbosity(key, value),
+                "syntax" => {
+                    if let Some(syntax) = self.expect_string(key, value) {
+                        if SyntaxMode::try_parse(syntax).is_none() {
+                            self.error(key, &format!("unknown syntax '{syntax}'"));
+                        }
+                    }
+                }
+                "csiMethods" => self.validate_csi_methods(key, value),
+                "parserOptions" => self.validate_parser_options(key, value),
+                _ => self.error(key, "unknown key"),
+            }
+        }
+    }
+
+    fn validate_options(&mut self, value: &Value, csi_methods: &CsiMethods) {
+        let options = match value {
+            Value::Null => return,
+            Value::Object(options) => options,
+            _ => return self.error("", "expected an object"),
+        };
+
+        for (key, value) in options.iter().filter(|(_, value)| !value.is_null()) {
+            match key.as_str() {
+                "literals" | "chainSourceMap" => {
+                    self.expect_bool(key, value);
+                }
+                "telemetryVerbosity" => self.validate_telemetry_verbosity(key, value),
+                "csiMethods" => self.validate_csi_methods_subset(key, value, csi_methods),
+                "parserOptions" => self.validate_parser_options(key, value),
+                _ => self.error(key, "unknown key"),
+            }
+        }
+    }
+
+    fn validate_csi_methods_subset(&mut self, key: &str, value: &Value, csi_methods: &CsiMethods) {
+        let sources = match value {
+            Value::Array(sources) => sources,
+            _ => return self.error(key, "expected an array"),
+        };
+
+        for (index, src) in sources.iter().enumerate() {
+            let src_key = format!("{key}[{index}]");
+            if let Some(src) = self.expect_string(&src_key, src) {
+                if !csi_methods.methods.iter().any(|method| method.src == src) {
+                    self.error(&src_key, &format!("unknown csi method '{src}'"));
+                }
+            }
+        }
+    }
+
+    fn validate_parser_options(&mut self, key: &str, value: &Value) {
+        let options = match value {
+            Value::Object(options) => options,
+            _ => return self.error(key, "expected an object"),
+        };
+
+        for (name, value) in options.iter().filter(|(_, value)| !value.is_null()) {
+            let key = format!("{key}.{name}");
+            match name.as_str() {
+                "decorators" | "decoratorsBeforeExport" => {
+                    self.expect_bool(&key, value);
+                }
+                "isModule" => {
+                    if let Some(is_module) = self.expect_string(&key, value) {
+                        if ModuleKind::try_parse(is_module).is_none() {
+                            self.error(
+                                &key,
+                                &format!(
+                                    "unknown module kind '{is_module}', expected one of auto, module, script, commonjs"
+                                ),
+                            );
+                        }
+                    }
+                }
+                "target" => {
+                    if let Some(target) = self.expect_string(&key, value) {
+                        if ParserOptions::try_parse_target(target).is_none() {
+                            self.error(&key, &format!("unknown target '{target}'"));
+                        }
+                    }
+                }
+                _ => self.error(&key, "unknown key"),
+            }
+        }
+    }
+
+    fn validate_telemetry_verbosity(&mut self, key: &str, value: &Value) {
+        if let Some(verbosity) = self.expect_string(key, value) {
+            if TelemetryVerbosity::try_parse(verbosity).is_none() {
+                self.error(key, &format!("unknown telemetry verbosity '{verbosity}'"));
+            }
+        }
+    }
+
+    fn validate_local_var_prefix(&mut self, key: &str, value: &Value) {
+        if let Some(prefix) = self.expect_string(key, value) {
+            let valid = !prefix.is_empty()
+                && prefix
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+            if !valid {
+                self.error(
+                    key,
+                    "expected a non empty string of [A-Za-z0-9_$] characters",
+                );
+            }
+        }
+    }
+
+    fn validate_csi_methods(&mut self, key: &str, value: &Value) {
+        let methods = match value {
+            Value::Array(methods) => methods,
+            _ => return self.error(key, "expected an array"),
+        };
+
+        let mut sources: Vec<(&str, Option<&str>, Option<&str>, CsiMethodKind)> = Vec::new();
+        for (index, method) in methods.iter().enumerate() {
+            let method_key = format!("{key}[{index}]");
+            let method = match method {
+                Value::Object(method) => method,
+                _ => {
+                    self.error(&method_key, "expected an object");
+                    continue;
+                }
+            };
+
+            if let Some(src) = self.validate_csi_method(&method_key, method) {
+                let receiver = method.get("receiver").and_then(Value::as_str);
+                let receiver_pattern = method.get("receiverPattern").and_then(Value::as_str);
+                // a constructor or a property read may share its src with a method: String(a) and
+                // new String(a), but after, before and around hook the same method calls
+                let kind = match method
+                    .get("kind")
+                    .and_then(Value::as_str)
+                    .and_then(CsiMethodKind::try_parse)
+                    .unwrap_or_default()
+                {
+                    CsiMethodKind::Before | CsiMethodKind::Around => CsiMethodKind::After,
+                    kind => kind,
+                };
+                if sources.contains(&(src, receiver, receiver_pattern, kind)) {
+                    let message = match (receiver, receiver_pattern) {
+                        (Some(receiver), _) => {
+                            format!("duplicate src '{src}' for receiver '{receiver}'")
+                        }
+                        (None, Some(pattern)) => {
+                            format!("duplicate src '{src}' for receiver pattern '{pattern}'")
+                        }
+                        (None, None) => format!("duplicate src '{src}'"),
+                    };
+                    self.error(&method_key, &message);
+                } else {
+                    sources.push((src, receiver, receiver_pattern, kind));
+                }
+            }
+        }
+    }
+
+    fn validate_csi_method<'a>(
+        &mut self,
+        method_key: &str,
+        method: &'a Map<String, Value>,
+    ) -> Option<&'a str> {
+        for (name, value) in method.iter().filter(|(_, value)| !value.is_null()) {
+            let key = format!("{method_key}.{name}");
+            match name.as_str() {
+                "src" | "dst" => {
+                    self.expect_string(&key, value);
+                }
+                "receiver" => {
+                    if self.expect_string(&key, value) == Some("") {
+                        self.error(&key, "expected a non empty string");
+                    }
+                }
+                "receiverPattern" => {
+                    if let Some(pattern) = self.expect_string(&key, value) {
+                        if !is_receiver_pattern(pattern) {
+                            self.error(
+                                &key,
+                                "expected a dotted path of identifiers like req.query",
+                            );
+                        }
+                    }
+                }
+                "operator" | "allowedWithoutCallee" => {
+                    self.expect_bool(&key, value);
+                }
+                "kind" => {
+                    if let Some(kind) = self.expect_string(&key, value) {
+                        if CsiMethodKind::try_parse(kind).is_none() {
+                            self.error(
+                                &key,
+                                &format!(
+                                    "unknown kind '{kind}', expected one of {}",
+                                    CsiMethodKind::NAMES.join(", ")
+                                ),
+                            );
+                        }
+                    }
+                }
+                _ => self.error(&key, "unknown key"),
+            }
+        }
+
+        if let None | Some(Value::Null) = method.get("src") {
+            self.error(&format!("{method_key}.src"), "missing required key");
+        }
+
+        let src = method.get("src").and_then(Value::as_str);
+
+        let operator = method
+            .get("operator")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if let Some(src) = src.filter(|src| operator && !OPERATORS.contains(src)) {
+            self.error(
+                &format!("{method_key}.src"),
+                &format!(
+                    "unknown operator '{src}', expected one of {}",
+                    OPERATORS.join(", ")
+                ),
+            );
+        }
+
+        let kind = method
+            .get("kind")
+            .and_then(Value::as_str)
+            .and_then(CsiMethodKind::try_parse);
+        if operator && kind.is_some_and(|kind| kind != CsiMethodKind::After) {
+            self.error(
+                &format!("{method_key}.kind"),
+                "operators only support kind 'after'",
+            );
+        }
+
+        if operator
+            && method
+                .get("receiver")
+                .is_some_and(|receiver| !receiver.is_null())
+        {
+            self.error(
+                &format!("{method_key}.receiver"),
+                "operators do not support receiver",
+            );
+        }
+
+        let has_receiver_pattern = method
+            .get("receiverPattern")
+            .is_some_and(|pattern| !pattern.is_null());
+        if kind == Some(CsiMethodKind::PropertyRead) && !has_receiver_pattern {
+            self.error(
+                &format!("{method_key}.receiverPattern"),
+                "missing required key for kind 'propertyRead'",
+            );
+        } else if kind != Some(CsiMethodKind::PropertyRead) && has_receiver_pattern {
+            self.error(
+                &format!("{method_key}.receiverPattern"),
+                "only supported by kind 'propertyRead'",
+            );
+        }
+
+        src
+    }
+
+    fn expect_bool(&mut self, key: &str, value: &Value) -> Option<bool> {
+        let bool_value = value.as_bool();
+        if bool_value.is_none() {
+            self.error(key, "expected a boolean");
+        }
+        bool_value
+    }
+
+    fn expect_string<'a>(&mut self, key: &str, value: &'a Value) -> Option<&'a str> {
+        let str_value = value.as_str();
+        if str_value.is_none() {
+            self.error(key, "expected a string");
+        }
+        str_value
+    }
+}
+
+// req.query or this.headers, every segment a valid identifier
+fn is_receiver_pattern(pattern: &str) -> bool {
+    pattern.split('.').all(|segment| {
+        segment
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+    })
+}
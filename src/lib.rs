@@ -3,6 +3,8 @@
 * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
 * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
 **/
+mod config;
+mod rewrite_error;
 mod rewriter;
 mod telemetry;
 mod tracer_logger;
@@ -10,7 +12,9 @@ mod transform;
 mod util;
 mod visitor;
 
+// the license header of the test files is followed by a blank line
 #[cfg(test)]
+#[allow(clippy::empty_line_after_doc_comments)]
 mod tests;
 
 #[cfg(not(feature = "napi"))]
@@ -7,68 +7,19 @@ extern crate base64;
 use std::collections::HashMap;
 
 use crate::{
+    config::{ConfigErrors, RewriteOptions, RewriterConfig},
     rewriter::{print_js, rewrite_js, Config},
-    telemetry::{Telemetry, TelemetryVerbosity},
+    telemetry::Telemetry,
+    tracer_logger,
     transform::transform_status::TransformStatus,
-    util::{rnd_string, DefaultFileReader},
-    visitor::{self, csi_methods::CsiMethods, literal_visitor},
+    util::DefaultFileReader,
+    visitor::{literal_visitor, site_visitor},
 };
+use log::{debug, error};
 
-use napi::{Error, Status};
-
-#[napi(object)]
-#[derive(Debug)]
-pub struct CsiMethod {
-    pub src: String,
-    pub dst: Option<String>,
-    pub operator: Option<bool>,
-    pub allowed_without_callee: Option<bool>,
-}
-
-#[napi(object)]
-#[derive(Debug)]
-pub struct RewriterConfig {
-    pub chain_source_map: Option<bool>,
-    pub comments: Option<bool>,
-    pub local_var_prefix: Option<String>,
-    pub csi_methods: Option<Vec<CsiMethod>>,
-    pub literals: Option<bool>,
-}
-
-impl RewriterConfig {
-    fn get_csi_methods(&self) -> CsiMethods {
-        match &self.csi_methods {
-            Some(methods_napi) => CsiMethods::new(
-                &methods_napi
-                    .iter()
-                    .map(|m| {
-                        visitor::csi_methods::CsiMethod::new(
-                            m.src.clone(),
-                            m.dst.clone(),
-                            m.operator.unwrap_or(false),
-                            m.allowed_without_callee.unwrap_or(false),
-                        )
-                    })
-                    .collect::<Vec<visitor::csi_methods::CsiMethod>>(),
-            ),
-            None => CsiMethods::empty(),
-        }
-    }
-
-    fn to_config(&self) -> Config {
-        Config {
-            chain_source_map: self.chain_source_map.unwrap_or(false),
-            print_comments: self.comments.unwrap_or(false),
-            local_var_prefix: self
-                .local_var_prefix
-                .clone()
-                .unwrap_or_else(|| rnd_string(6)),
-            csi_methods: self.get_csi_methods(),
-            verbosity: TelemetryVerbosity::Information,
-            literals: self.literals.unwrap_or(true),
-        }
-    }
-}
+use napi::{Env, Error, JsObject, JsUnknown, Status};
+use serde::Serialize;
+use serde_json::Value;
 
 #[napi(object, js_name = "Result")]
 #[derive(Debug)]
@@ -76,6 +27,8 @@ pub struct RewriteResult {
     pub content: String,
     pub metrics: Option<Metrics>,
     pub literals_result: Option<LiteralsResult>,
+    pub location_files: Option<Vec<String>>,
+    pub sites: Option<Vec<SiteInfo>>,
 }
 
 #[napi(object)]
@@ -129,6 +82,33 @@ impl LiteralInfo {
     }
 }
 
+#[napi(object)]
+#[derive(Debug)]
+pub struct SiteInfo {
+    pub id: u32,
+    pub kind: String,
+    pub tag: String,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub function: Option<String>,
+}
+
+impl SiteInfo {
+    fn from(sites: Vec<site_visitor::SiteInfo>) -> Vec<SiteInfo> {
+        sites
+            .into_iter()
+            .map(|site| SiteInfo {
+                id: site.id as u32,
+                kind: site.kind,
+                tag: site.tag,
+                line: site.line.map(|line| line as u32),
+                column: site.column.map(|column| column as u32),
+                function: site.function,
+            })
+            .collect()
+    }
+}
+
 #[napi]
 pub struct Rewriter {
     config: Config,
@@ -137,47 +117,101 @@ pub struct Rewriter {
 #[napi]
 impl Rewriter {
     #[napi(constructor)]
-    pub fn new(config: Option<RewriterConfig>) -> Self {
-        let rewriter_config: RewriterConfig = config.unwrap_or(RewriterConfig {
-            chain_source_map: Some(false),
-            comments: Some(false),
-            local_var_prefix: None,
-            csi_methods: None,
-            literals: Some(true),
-        });
-        Self {
-            config: rewriter_config.to_config(),
+    pub fn new(env: Env, config: Option<JsUnknown>) -> napi::Result<Self> {
+        let config_value = match config {
+            Some(config) => env
+                .from_js_value::<Value, _>(config)
+                .map_err(|err| ConfigErrors::new("", &err.to_string())),
+            None => Ok(Value::Null),
+        };
+
+        let rewriter_config = config_value.and_then(RewriterConfig::from_value);
+        match rewriter_config {
+            Ok(rewriter_config) => Ok(Self {
+                config: rewriter_config.to_config(),
+            }),
+            Err(errors) => {
+                error!("{errors}");
+                throw_error(&env, &errors.to_string(), &errors)
+            }
         }
     }
 
     #[napi]
-    pub fn rewrite(&self, code: String, file: String) -> napi::Result<RewriteResult> {
+    pub fn rewrite(
+        &self,
+        env: Env,
+        code: String,
+        file: String,
+        options: Option<JsUnknown>,
+    ) -> napi::Result<RewriteResult> {
         let default_file_reader = DefaultFileReader {};
 
-        rewrite_js(code, &file, &self.config, &default_file_reader)
+        let options_value = match options {
+            Some(options) => env
+                .from_js_value::<Value, _>(options)
+                .map_err(|err| ConfigErrors::new("", &err.to_string())),
+            None => Ok(Value::Null),
+        };
+        let options = match options_value
+            .and_then(|options| RewriteOptions::from_value(options, &self.config))
+        {
+            Ok(options) => options,
+            Err(errors) => {
+                error!("Error rewriting {}: {}", &file, errors);
+                return throw_error(&env, &errors.to_string(), &errors);
+            }
+        };
+        let config = options.apply(&self.config);
+
+        rewrite_js(code, &file, &config, &default_file_reader)
             .map(|result| RewriteResult {
-                content: print_js(&result, &self.config),
+                content: print_js(
+                    &result.code,
+                    &result.source_map,
+                    &result.original_source_map,
+                    &config,
+                )
+                .into_owned(),
                 metrics: get_metrics(result.transform_status, &file),
                 literals_result: match result.literals_result {
                     Some(literals_result) => Some(LiteralsResult {
-                        file,
+                        file: file.clone(),
                         literals: LiteralInfo::from(literals_result.literals),
                     }),
                     _ => None,
                 },
+                location_files: result.location_files,
+                sites: result.sites.map(SiteInfo::from),
+            })
+            .inspect(|result| {
+                let status = &result.metrics;
+                debug!("Rewritten {file}\n status {status:?}");
+            })
+            .or_else(|e| {
+                error!("Error rewriting {}: {}", &file, e);
+                throw_error(&env, &e.to_string(), &e)
             })
-            .map_err(|e| Error::new(Status::Unknown, format!("{e}")))
     }
 
     #[napi]
     pub fn csi_methods(&self) -> napi::Result<Vec<String>> {
-        let csi_methods = &self.config.csi_methods;
+        Ok(self.config.csi_methods.hook_names())
+    }
 
-        Ok(csi_methods
-            .methods
-            .iter()
-            .map(|csi_method| csi_method.dst.clone())
-            .collect())
+    #[napi]
+    pub fn set_logger(
+        &self,
+        env: Env,
+        logger: Option<JsObject>,
+        level: String,
+    ) -> napi::Result<()> {
+        tracer_logger::set_logger(&env, logger, &level).map(|_| {
+            log::log!(
+                log::max_level().to_level().unwrap_or(log::Level::Error),
+                "IAST rewriter logger configured OK"
+            )
+        })
     }
 }
 
@@ -192,3 +226,15 @@ fn get_metrics(status: Option<TransformStatus>, file: &str) -> Option<Metrics> {
     }
     None
 }
+
+// throws a js Error with the serialized error fields as properties
+fn throw_error<T>(env: &Env, message: &str, properties: &impl Serialize) -> napi::Result<T> {
+    let mut js_error = env.create_error(Error::new(Status::GenericFailure, message.to_string()))?;
+    if let Ok(Value::Object(properties)) = serde_json::to_value(properties) {
+        for (key, value) in properties {
+            js_error.set_named_property(&key, env.to_js_value(&value)?)?;
+        }
+    }
+    env.throw(js_error)?;
+    Err(Error::new(Status::PendingException, message.to_string()))
+}
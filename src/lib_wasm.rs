@@ -5,15 +5,17 @@
 extern crate base64;
 
 use crate::{
-    rewriter::{generate_prefix_stmts, print_js, rewrite_js, Config},
-    telemetry::{Telemetry, TelemetryVerbosity},
+    config::{ConfigErrors, RewriteOptions, RewriterConfig},
+    rewriter::{print_js, rewrite_js, Config},
+    telemetry::Telemetry,
     tracer_logger::{self},
     transform::transform_status::TransformStatus,
-    util::{rnd_string, FileReader},
-    visitor::{self, csi_methods::CsiMethods, literal_visitor::LiteralsResult},
+    util::FileReader,
+    visitor::{literal_visitor::LiteralsResult, site_visitor::SiteInfo},
 };
 use log::{debug, error};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+use serde_json::Value;
 use std::{
     collections::HashMap,
     io::{Cursor, Read},
@@ -21,32 +23,14 @@ use std::{
 };
 use wasm_bindgen::{prelude::wasm_bindgen, JsError, JsValue};
 
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CsiMethod {
-    pub src: String,
-    pub dst: Option<String>,
-    pub operator: Option<bool>,
-    pub allowed_without_callee: Option<bool>,
-}
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RewriterConfig {
-    pub chain_source_map: Option<bool>,
-    pub comments: Option<bool>,
-    pub local_var_prefix: Option<String>,
-    pub csi_methods: Option<Vec<CsiMethod>>,
-    pub telemetry_verbosity: Option<String>,
-    pub literals: Option<bool>,
-}
-
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Result {
     pub content: String,
     pub metrics: Option<Metrics>,
     pub literals_result: Option<LiteralsResult>,
+    pub location_files: Option<Vec<String>>,
+    pub sites: Option<Vec<SiteInfo>>,
 }
 
 #[derive(Serialize, Debug)]
@@ -58,57 +42,6 @@ pub struct Metrics {
     pub propagation_debug: Option<HashMap<String, u32>>,
 }
 
-impl RewriterConfig {
-    fn default() -> Self {
-        RewriterConfig {
-            chain_source_map: Some(false),
-            comments: Some(false),
-            local_var_prefix: None,
-            csi_methods: None,
-            telemetry_verbosity: Some("INFORMATION".to_string()),
-            literals: Some(true),
-        }
-    }
-
-    fn get_csi_methods(&self) -> CsiMethods {
-        match &self.csi_methods {
-            Some(methods) => CsiMethods::new(
-                &methods
-                    .iter()
-                    .map(|m| {
-                        visitor::csi_methods::CsiMethod::new(
-                            m.src.clone(),
-                            m.dst.clone(),
-                            m.operator.unwrap_or(false),
-                            m.allowed_without_callee.unwrap_or(false),
-                        )
-                    })
-                    .collect::<Vec<visitor::csi_methods::CsiMethod>>(),
-            ),
-
-            None => CsiMethods::empty(),
-        }
-    }
-
-    fn to_config(&self) -> Config {
-        let csi_methods = self.get_csi_methods();
-        let file_prefix_code = generate_prefix_stmts(&csi_methods);
-
-        Config {
-            chain_source_map: self.chain_source_map.unwrap_or(false),
-            print_comments: self.comments.unwrap_or(false),
-            local_var_prefix: self
-                .local_var_prefix
-                .clone()
-                .unwrap_or_else(|| rnd_string(6)),
-            csi_methods,
-            verbosity: TelemetryVerbosity::parse(self.telemetry_verbosity.clone()),
-            literals: self.literals.unwrap_or(true),
-            file_prefix_code,
-        }
-    }
-}
-
 #[wasm_bindgen]
 pub struct Rewriter {
     config: Config,
@@ -139,13 +72,9 @@ impl FileReader<Cursor<Vec<u8>>> for WasmFileReader {
                     Cursor::new(arr.to_vec())
                 })
                 .map_err(|err| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Error reading source map from wasm {err:?}"),
-                    )
+                    std::io::Error::other(format!("Error reading source map from wasm {err:?}"))
                 }),
-            None => Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
+            None => Err(std::io::Error::other(
                 "Error reading source map. No path provided".to_string(),
             )),
         }
@@ -167,32 +96,52 @@ impl FileReader<Cursor<Vec<u8>>> for WasmFileReader {
 #[wasm_bindgen]
 impl Rewriter {
     #[wasm_bindgen(constructor)]
-    pub fn new(config_js: JsValue) -> Self {
+    pub fn new(config_js: JsValue) -> anyhow::Result<Rewriter, JsValue> {
         std::panic::set_hook(Box::new(console_error_panic_hook::hook));
 
-        let rewriter_config = serde_wasm_bindgen::from_value::<RewriterConfig>(config_js);
-        let config: Config = rewriter_config
-            .unwrap_or(RewriterConfig::default())
+        let config: Config = serde_wasm_bindgen::from_value::<Value>(config_js)
+            .map_err(|err| ConfigErrors::new("", &err.to_string()))
+            .and_then(RewriterConfig::from_value)
+            .map_err(|errors| {
+                error!("{errors}");
+                get_js_error(&errors.to_string(), &errors)
+            })?
             .to_config();
 
-        Self { config }
+        Ok(Self { config })
     }
 
     #[wasm_bindgen]
-    pub fn rewrite(&mut self, code: String, file: String) -> anyhow::Result<JsValue, JsError> {
+    pub fn rewrite(
+        &mut self,
+        code: String,
+        file: String,
+        options: JsValue,
+    ) -> anyhow::Result<JsValue, JsValue> {
         let source_map_reader = WasmFileReader {};
 
-        rewrite_js(code, &file, &self.config, &source_map_reader)
+        let options = serde_wasm_bindgen::from_value::<Value>(options)
+            .map_err(|err| ConfigErrors::new("", &err.to_string()))
+            .and_then(|options| RewriteOptions::from_value(options, &self.config))
+            .map_err(|errors| {
+                error!("Error rewriting {}: {}", &file, errors);
+                get_js_error(&errors.to_string(), &errors)
+            })?;
+        let config = options.apply(&self.config);
+
+        rewrite_js(code, &file, &config, &source_map_reader)
             .map(|result| Result {
                 content: print_js(
                     &result.code,
                     &result.source_map,
                     &result.original_source_map,
-                    &self.config,
+                    &config,
                 )
                 .into_owned(),
                 metrics: get_metrics(result.transform_status, &file),
                 literals_result: result.literals_result,
+                location_files: result.location_files,
+                sites: result.sites,
             })
             .as_ref()
             .map(|result| {
@@ -202,22 +151,16 @@ impl Rewriter {
                 serde_wasm_bindgen::to_value(result).unwrap()
             })
             .map_err(|e| {
-                let error_msg = format!("{e}");
-                error!("Error rewriting {}: {}", &file, &error_msg);
-                JsError::new(&error_msg)
+                error!("Error rewriting {}: {}", &file, e);
+                get_js_error(&e.to_string(), e)
             })
     }
 
     #[wasm_bindgen(js_name = csiMethods)]
     pub fn csi_methods(&self) -> anyhow::Result<JsValue, JsError> {
-        let csi_methods = &self.config.csi_methods;
-        let dst_methods = csi_methods
-            .methods
-            .iter()
-            .map(|csi_method| csi_method.dst.clone())
-            .collect::<Vec<String>>();
-
-        serde_wasm_bindgen::to_value(&dst_methods).map_err(|e| {
+        let hook_names = self.config.csi_methods.hook_names();
+
+        serde_wasm_bindgen::to_value(&hook_names).map_err(|e| {
             let error_msg = format!("{e}");
             error!("Error getting csi methods: {}", &error_msg);
             JsError::new(&error_msg)
@@ -248,3 +191,12 @@ fn get_metrics(status: Option<TransformStatus>, file: &str) -> Option<Metrics> {
     }
     None
 }
+
+// js Error with the serialized error fields as properties
+fn get_js_error(message: &str, properties: &impl Serialize) -> JsValue {
+    let js_error = js_sys::Error::new(message);
+    if let Ok(js_properties) = serde_wasm_bindgen::to_value(properties) {
+        js_sys::Object::assign(&js_error, &js_properties.into());
+    }
+    js_error.into()
+}
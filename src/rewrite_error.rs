@@ -0,0 +1,92 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use serde::Serialize;
+use std::{any::Any, fmt};
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "code")]
+pub enum RewriteErrorCode {
+    ParseError,
+    SourceMapError,
+    // only returned by the native addon, wasm is built with panic=abort and can not catch panics
+    Panic,
+}
+
+/// Error returned by the rewriter. It is exposed to js as an `Error` with the serialized fields
+/// as properties: `{ code, file, line?, column?, diagnostic }`. Its `message` is the `Display`
+/// output, prefixed with the file and the kind of error, and `diagnostic` the bare message.
+/// Line is 1-based and column is 0-based, as in swc and source maps.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteError {
+    #[serde(flatten)]
+    pub code: RewriteErrorCode,
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    #[serde(rename = "diagnostic")]
+    pub message: String,
+}
+
+impl RewriteError {
+    pub fn parse_error(file: &str, line: usize, column: usize, message: String) -> Self {
+        RewriteError {
+            code: RewriteErrorCode::ParseError,
+            file: file.to_string(),
+            line: Some(line),
+            column: Some(column),
+            message,
+        }
+    }
+
+    pub fn source_map_error(file: &str, message: String) -> Self {
+        RewriteError {
+            code: RewriteErrorCode::SourceMapError,
+            file: file.to_string(),
+            line: None,
+            column: None,
+            message,
+        }
+    }
+
+    pub fn panic(file: &str, payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload
+                .downcast::<&str>()
+                .map(|message| message.to_string())
+                .unwrap_or_else(|_| "unknown".to_string()),
+        };
+
+        RewriteError {
+            code: RewriteErrorCode::Panic,
+            file: file.to_string(),
+            line: None,
+            column: None,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = &self.file;
+        let message = &self.message;
+        match (&self.code, self.line, self.column) {
+            (RewriteErrorCode::ParseError, Some(line), Some(column)) => {
+                write!(f, "Error parsing {file}:{line}:{column}: {message}")
+            }
+            (RewriteErrorCode::ParseError, _, _) => write!(f, "Error parsing {file}: {message}"),
+            (RewriteErrorCode::SourceMapError, _, _) => {
+                write!(f, "Error generating {file} source map: {message}")
+            }
+            (RewriteErrorCode::Panic, _, _) => write!(f, "Panic rewriting {file}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RewriteError {}
@@ -3,6 +3,7 @@
 * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
 **/
 use crate::{
+    rewrite_error::RewriteError,
     telemetry::TelemetryVerbosity,
     transform::transform_status::{Status, TransformStatus},
     util::{file_name, parse_source_map, FileReader},
@@ -10,6 +11,9 @@ use crate::{
         block_transform_visitor::BlockTransformVisitor,
         csi_methods::CsiMethods,
         literal_visitor::{get_literals, LiteralsResult},
+        location_visitor::add_locations,
+        site_visitor::{get_sites, SiteInfo},
+        symbol_table::SymbolTable,
     },
 };
 use anyhow::{Error, Result};
@@ -19,25 +23,28 @@ use std::{
     borrow::Cow,
     collections::HashMap,
     io::Read,
+    panic::{self, AssertUnwindSafe},
     path::{Path, PathBuf},
     str,
     sync::Arc,
 };
 use swc::{
-    config::{IsModule, SourceMapsConfig},
+    config::SourceMapsConfig,
     sourcemap::{decode, decode_data_url, DecodedMap, SourceMap, SourceMapBuilder},
-    try_with_handler, Compiler, HandlerOpts, PrintArgs, SwcComments,
+    Compiler, PrintArgs, SwcComments,
 };
 use swc_common::{
-    comments::Comments,
-    errors::{ColorConfig, Handler},
-    FileName, FilePathMapping, SourceFile,
+    comments::Comments, FileName, FilePathMapping, Globals, Mark, SourceFile, Spanned, GLOBALS,
 };
 use swc_ecma_ast::{EsVersion, Program, Stmt};
 
 use std::fmt;
-use swc_ecma_parser::{EsSyntax, Syntax};
-use swc_ecma_visit::VisitMutWith;
+use swc_ecma_parser::{
+    parse_file_as_module, parse_file_as_program, parse_file_as_script, EsSyntax, Syntax, TsSyntax,
+};
+use swc_ecma_transforms_base::{fixer::fixer, resolver};
+use swc_ecma_transforms_typescript::strip;
+use swc_ecma_visit::{FoldWith, VisitMutWith};
 
 const SOURCE_MAP_URL: &str = "# sourceMappingURL=";
 
@@ -47,6 +54,8 @@ pub struct RewrittenOutput {
     pub original_source_map: OriginalSourceMap,
     pub transform_status: Option<TransformStatus>,
     pub literals_result: Option<LiteralsResult>,
+    pub location_files: Option<Vec<String>>,
+    pub sites: Option<Vec<SiteInfo>>,
 }
 
 pub struct OriginalSourceMap {
@@ -54,6 +63,7 @@ pub struct OriginalSourceMap {
     pub source_map_comment: Option<String>,
 }
 
+#[derive(Clone)]
 pub struct Config {
     pub chain_source_map: bool,
     pub print_comments: bool,
@@ -62,6 +72,12 @@ pub struct Config {
     pub verbosity: TelemetryVerbosity,
     pub literals: bool,
     pub file_prefix_code: Vec<Stmt>,
+    pub syntax: SyntaxMode,
+    pub strip_types: bool,
+    pub jsx: bool,
+    pub parser_options: ParserOptions,
+    pub call_site_locations: bool,
+    pub site_ids: bool,
 }
 
 impl fmt::Debug for Config {
@@ -74,29 +90,139 @@ impl fmt::Debug for Config {
             .field("verbosity", &self.verbosity)
             .field("literals", &self.literals)
             // file_prefix_code intentionally ignored
+            .field("syntax", &self.syntax)
+            .field("strip_types", &self.strip_types)
+            .field("jsx", &self.jsx)
+            .field("parser_options", &self.parser_options)
+            .field("call_site_locations", &self.call_site_locations)
+            .field("site_ids", &self.site_ids)
             .finish()
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SyntaxMode {
+    Auto,
+    Ecmascript,
+    Typescript,
+}
+
+impl SyntaxMode {
+    pub fn parse(optional_value: Option<String>) -> Self {
+        optional_value
+            .and_then(|value| SyntaxMode::try_parse(&value))
+            .unwrap_or(SyntaxMode::Auto)
+    }
+
+    pub fn try_parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "auto" => Some(SyntaxMode::Auto),
+            "ecmascript" => Some(SyntaxMode::Ecmascript),
+            "typescript" => Some(SyntaxMode::Typescript),
+            _ => None,
+        }
+    }
+
+    // Auto mode infers the syntax from the file extension
+    fn resolve(self, file: &str) -> Self {
+        match self {
+            SyntaxMode::Auto => match Path::new(file).extension().and_then(|ext| ext.to_str()) {
+                Some("ts" | "mts" | "cts" | "tsx") => SyntaxMode::Typescript,
+                _ => SyntaxMode::Ecmascript,
+            },
+            syntax => syntax,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ModuleKind {
+    Auto,
+    Module,
+    Script,
+    CommonJs,
+}
+
+impl ModuleKind {
+    pub fn try_parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "auto" => Some(ModuleKind::Auto),
+            "module" => Some(ModuleKind::Module),
+            "script" => Some(ModuleKind::Script),
+            "commonjs" => Some(ModuleKind::CommonJs),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ParserOptions {
+    pub decorators: bool,
+    pub decorators_before_export: bool,
+    pub is_module: ModuleKind,
+    pub target: EsVersion,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            decorators: false,
+            decorators_before_export: false,
+            is_module: ModuleKind::Auto,
+            target: EsVersion::latest(),
+        }
+    }
+}
+
+impl ParserOptions {
+    pub fn try_parse_target(value: &str) -> Option<EsVersion> {
+        match value.to_lowercase().as_str() {
+            "es3" => Some(EsVersion::Es3),
+            "es5" => Some(EsVersion::Es5),
+            "es6" | "es2015" => Some(EsVersion::Es2015),
+            "es2016" => Some(EsVersion::Es2016),
+            "es2017" => Some(EsVersion::Es2017),
+            "es2018" => Some(EsVersion::Es2018),
+            "es2019" => Some(EsVersion::Es2019),
+            "es2020" => Some(EsVersion::Es2020),
+            "es2021" => Some(EsVersion::Es2021),
+            "es2022" => Some(EsVersion::Es2022),
+            "esnext" | "latest" => Some(EsVersion::latest()),
+            _ => None,
+        }
+    }
+}
+
 pub fn rewrite_js<R: Read>(
     code: String,
     file: &str,
     config: &Config,
     file_reader: &impl FileReader<R>,
-) -> Result<RewrittenOutput> {
+) -> Result<RewrittenOutput, RewriteError> {
     debug!("Rewriting js file: {file} with config: {config:?}");
 
-    let compiler = Compiler::new(Arc::new(swc_common::SourceMap::new(
-        FilePathMapping::empty(),
-    )));
-    try_with_handler(compiler.cm.clone(), default_handler_opts(), |handler| {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let compiler = Compiler::new(Arc::new(swc_common::SourceMap::new(
+            FilePathMapping::empty(),
+        )));
         let source_file = compiler
             .cm
             .new_source_file(Arc::new(FileName::Real(PathBuf::from(file))), code);
 
-        parse_js(&source_file, handler, &compiler)
-            .and_then(|program| transform_js(program, file, file_reader, config, &compiler))
-    })
+        parse_js(
+            &source_file,
+            file,
+            config.syntax,
+            config.jsx,
+            &config.parser_options,
+            &compiler,
+        )
+        .map(|program| strip_types(program, file, config))
+        .and_then(|(program, stripped)| {
+            transform_js(program, stripped, file, file_reader, config, &compiler)
+        })
+    }))
+    .unwrap_or_else(|payload| Err(RewriteError::panic(file, payload)))
 }
 
 pub fn print_js<'a>(
@@ -136,53 +262,128 @@ pub fn print_js<'a>(
     }
 }
 
-fn default_handler_opts() -> HandlerOpts {
-    HandlerOpts {
-        color: ColorConfig::Never,
-        skip_filename: false,
-    }
-}
-
 fn parse_js(
     source_file: &Arc<SourceFile>,
-    handler: &Handler,
+    file: &str,
+    syntax: SyntaxMode,
+    jsx: bool,
+    parser_options: &ParserOptions,
     compiler: &Compiler,
-) -> Result<Program> {
-    let es_syntax = EsSyntax {
-        jsx: false,
-        fn_bind: false,
-        decorators: false,
-        decorators_before_export: false,
-        export_default_from: false,
-        import_attributes: true,
-        allow_super_outside_method: false,
-        allow_return_outside_function: true,
-        auto_accessors: true,
-        ..Default::default()
+) -> Result<Program, RewriteError> {
+    // .ts files can not be parsed as tsx because of the <T>expr type assertions. Typescript
+    // decorators are always parsed, the decorators parser options only apply to js files
+    let syntax = match syntax.resolve(file) {
+        SyntaxMode::Typescript => Syntax::Typescript(TsSyntax {
+            tsx: file.ends_with(".tsx"),
+            decorators: true,
+            ..Default::default()
+        }),
+        _ => Syntax::Es(EsSyntax {
+            jsx,
+            fn_bind: false,
+            decorators: parser_options.decorators,
+            decorators_before_export: parser_options.decorators_before_export,
+            export_default_from: false,
+            import_attributes: true,
+            allow_super_outside_method: false,
+            // commonjs modules are wrapped in a function so top level returns are allowed
+            allow_return_outside_function: matches!(
+                parser_options.is_module,
+                ModuleKind::Auto | ModuleKind::CommonJs
+            ),
+            auto_accessors: true,
+            ..Default::default()
+        }),
     };
 
-    compiler.parse_js(
-        source_file.to_owned(),
-        handler,
-        EsVersion::latest(),
-        Syntax::Es(es_syntax),
-        IsModule::Unknown,
-        Some(&compiler.comments() as &dyn Comments),
-    )
+    let target = parser_options.target;
+    let comments = Some(&compiler.comments() as &dyn Comments);
+    let mut recovered_errors = vec![];
+    let program = match parser_options.is_module {
+        ModuleKind::Auto => {
+            parse_file_as_program(source_file, syntax, target, comments, &mut recovered_errors)
+        }
+        ModuleKind::Module => {
+            parse_file_as_module(source_file, syntax, target, comments, &mut recovered_errors)
+                .map(Program::Module)
+        }
+        ModuleKind::Script | ModuleKind::CommonJs => {
+            parse_file_as_script(source_file, syntax, target, comments, &mut recovered_errors)
+                .map(Program::Script)
+        }
+    };
+
+    // recovered errors are syntax errors too, report the first one
+    match (program, recovered_errors.into_iter().next()) {
+        (Ok(program), None) => Ok(program),
+        (Err(error), _) | (Ok(_), Some(error)) => {
+            let loc = compiler.cm.lookup_char_pos(error.span().lo);
+            Err(RewriteError::parse_error(
+                file,
+                loc.line,
+                loc.col_display,
+                error.kind().msg().to_string(),
+            ))
+        }
+    }
+}
+
+// Types are stripped before the transformation so typescript specific syntax (enums,
+// namespaces, parameter properties...) is instrumented as the emitted js
+fn strip_types(program: Program, file: &str, config: &Config) -> (Program, bool) {
+    if !config.strip_types || config.syntax.resolve(file) != SyntaxMode::Typescript {
+        return (program, false);
+    }
+
+    let program = GLOBALS.set(&Globals::new(), || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+
+        program
+            .fold_with(&mut resolver(unresolved_mark, top_level_mark, true))
+            .fold_with(&mut strip(unresolved_mark, top_level_mark))
+            .fold_with(&mut fixer(None))
+    });
+    (program, true)
+}
+
+// Module receivers are matched on the binding an identifier resolves to so the resolver marks
+// every identifier with its scope. A program with stripped types is already resolved
+fn resolve_bindings(program: Program, file: &str, config: &Config) -> Program {
+    let typescript = config.syntax.resolve(file) == SyntaxMode::Typescript;
+    GLOBALS.set(&Globals::new(), || {
+        let unresolved_mark = Mark::new();
+        let top_level_mark = Mark::new();
+
+        program.fold_with(&mut resolver(unresolved_mark, top_level_mark, typescript))
+    })
 }
 
 fn transform_js<R: Read>(
     mut program: Program,
+    types_stripped: bool,
     file: &str,
     file_reader: &impl FileReader<R>,
     config: &Config,
     compiler: &Compiler,
-) -> Result<RewrittenOutput, Error> {
+) -> Result<RewrittenOutput, RewriteError> {
     let mut transform_status = TransformStatus::not_modified(config);
 
-    let mut block_transform_visitor = BlockTransformVisitor::default(&mut transform_status, config);
+    // bindings are keyed by name when no csi method needs the module a binding refers to
+    if config.csi_methods.has_receivers() && !types_stripped {
+        program = resolve_bindings(program, file, config);
+    }
+    let symbol_table = SymbolTable::collect(&program);
+
+    let mut block_transform_visitor =
+        BlockTransformVisitor::default(&mut transform_status, config, &symbol_table);
     program.visit_mut_with(&mut block_transform_visitor);
 
+    // code without types must be printed even if it is not instrumented
+    if types_stripped && transform_status.status == Status::NotModified {
+        transform_status.status = Status::Modified;
+    }
+
     let literals_result = get_literals(config.literals, file, &mut program, compiler);
     let comments = &compiler.comments().clone() as &dyn Comments;
 
@@ -200,6 +401,25 @@ fn transform_js<R: Read>(
             // and looks like it is not possible to read them after compiler.print() invocation
             let original_source_map = extract_source_map(file, compiler.comments(), file_reader);
 
+            // site ids must be read before the location tokens are appended
+            let sites = get_sites(
+                config.site_ids,
+                file,
+                &program,
+                &transform_status.sites,
+                original_source_map.source.as_ref(),
+                compiler,
+            );
+
+            let location_files = add_locations(
+                config.call_site_locations,
+                file,
+                &mut program,
+                &config.csi_methods,
+                original_source_map.source.as_ref(),
+                compiler,
+            );
+
             compiler
                 .print(&program, print_args)
                 .map(|output| RewrittenOutput {
@@ -208,7 +428,10 @@ fn transform_js<R: Read>(
                     original_source_map,
                     transform_status: Some(transform_status),
                     literals_result,
+                    location_files,
+                    sites,
                 })
+                .map_err(|err| RewriteError::source_map_error(file, format!("{err}")))
         }
 
         Status::NotModified => Ok(RewrittenOutput {
@@ -220,15 +443,9 @@ fn transform_js<R: Read>(
             },
             transform_status: Some(transform_status),
             literals_result,
+            location_files: None,
+            sites: None,
         }),
-
-        Status::Cancelled => Err(Error::msg(format!(
-            "Cancelling {} file rewrite. Reason: {}",
-            file,
-            transform_status
-                .msg
-                .unwrap_or_else(|| "unknown".to_string())
-        ))),
     }
 }
 
@@ -337,34 +554,76 @@ fn extract_source_map<R: Read>(
     }
 }
 
-pub fn generate_prefix_stmts(csi_methods: &CsiMethods) -> Vec<Stmt> {
-    let template = ";if (typeof _ddiast === 'undefined') (function(globals){ const noop = (res) => res; globals._ddiast = globals._ddiast || { __CSI_METHODS__ }; }((1,eval)('this')));";
+pub fn generate_prefix_stmts(
+    csi_methods: &CsiMethods,
+    call_site_locations: bool,
+    site_ids: bool,
+) -> Vec<Stmt> {
+    let template = ";if (typeof _ddiast === 'undefined') (function(globals){ const noop = (__LEADING_PARAMS__res) => res__NOOP_BEFORE__; globals._ddiast = globals._ddiast || { __CSI_METHODS__ }; }((1,eval)('this')));";
+
+    // the noops skip the arguments passed before the hooked value: the location token and the
+    // site id
+    let leading_params = [(call_site_locations, "location, "), (site_ids, "siteId, ")]
+        .iter()
+        .filter_map(|(enabled, param)| enabled.then_some(*param))
+        .collect::<String>();
 
     let csi_methods_code = csi_methods
         .methods
         .iter()
-        .map(|csi_method| format!("{}: noop", csi_method.dst))
-        .collect::<Vec<_>>()
+        .flat_map(|csi_method| {
+            let before_dst = csi_method.before_dst();
+            csi_method.hook_names().into_iter().map(move |name| {
+                let noop = if name == before_dst {
+                    "noopBefore"
+                } else {
+                    "noop"
+                };
+                format!("{name}: {noop}")
+            })
+        })
+        // several csi methods may share a hook, like trimStart and trimEnd
+        .fold(Vec::new(), |mut hooks, hook| {
+            if !hooks.contains(&hook) {
+                hooks.push(hook);
+            }
+            hooks
+        })
         .join(", ");
 
-    let final_template = template.replace("__CSI_METHODS__", &csi_methods_code);
+    // before hooks return the arguments of the original call
+    let noop_before_code = if csi_methods
+        .methods
+        .iter()
+        .any(|csi_method| csi_method.kind.has_before_hook())
+    {
+        ", noopBefore = (__LEADING_PARAMS__fn, self, args) => args"
+    } else {
+        ""
+    };
+
+    let final_template = template
+        .replace("__NOOP_BEFORE__", noop_before_code)
+        .replace("__LEADING_PARAMS__", &leading_params)
+        .replace("__CSI_METHODS__", &csi_methods_code);
 
     let compiler = Compiler::new(Arc::new(swc_common::SourceMap::new(
         FilePathMapping::empty(),
     )));
 
-    let handler_opts = HandlerOpts {
-        color: ColorConfig::Never,
-        skip_filename: false,
-    };
-    let program_result = try_with_handler(compiler.cm.clone(), handler_opts, |handler| {
-        let source_file = compiler.cm.new_source_file(
-            Arc::new(FileName::Real(PathBuf::from("inline.js".to_string()))),
-            final_template.clone(),
-        );
-
-        parse_js(&source_file, handler, &compiler)
-    });
+    let js_file = "inline.js".to_string();
+    let source_file = compiler.cm.new_source_file(
+        Arc::new(FileName::Real(PathBuf::from(js_file.clone()))),
+        final_template,
+    );
+    let program_result = parse_js(
+        &source_file,
+        &js_file,
+        SyntaxMode::Ecmascript,
+        false,
+        &ParserOptions::default(),
+        &compiler,
+    );
 
     if let Ok(Program::Script(script)) = program_result {
         return script.body;
@@ -382,37 +641,44 @@ pub fn debug_js(code: String) -> Result<RewrittenOutput> {
     let compiler = Compiler::new(Arc::new(swc_common::SourceMap::new(
         FilePathMapping::empty(),
     )));
-    return try_with_handler(compiler.cm.clone(), default_handler_opts(), |handler| {
-        let js_file = "debug.js".to_string();
-        let source_file = compiler.cm.new_source_file(
-            Arc::new(FileName::Real(PathBuf::from(js_file.clone()))),
-            code,
-        );
-
-        let program = parse_js(&source_file, handler, &compiler)?;
-
-        print!("{:#?}", program);
-
-        let source_map_reader = DefaultFileReader {};
-        let original_map =
-            extract_source_map(js_file.as_str(), &compiler.comments(), &source_map_reader);
-
-        let print_args = PrintArgs {
-            source_file_name: file_name(&js_file),
-            source_map: SourceMapsConfig::Bool(true),
-            comments: Some(compiler.comments() as &dyn Comments),
-            emit_source_map_columns: true,
-            ..Default::default()
-        };
+    let js_file = "debug.js".to_string();
+    let source_file = compiler.cm.new_source_file(
+        Arc::new(FileName::Real(PathBuf::from(js_file.clone()))),
+        code,
+    );
+
+    let program = parse_js(
+        &source_file,
+        &js_file,
+        SyntaxMode::Auto,
+        true,
+        &ParserOptions::default(),
+        &compiler,
+    )?;
+
+    print!("{:#?}", program);
+
+    let source_map_reader = DefaultFileReader {};
+    let original_map =
+        extract_source_map(js_file.as_str(), compiler.comments(), &source_map_reader);
 
-        let print_result = compiler.print(&program, print_args);
+    let print_args = PrintArgs {
+        source_file_name: file_name(&js_file),
+        source_map: SourceMapsConfig::Bool(true),
+        comments: Some(compiler.comments() as &dyn Comments),
+        emit_source_map_columns: true,
+        ..Default::default()
+    };
 
-        print_result.map(|printed| RewrittenOutput {
-            code: printed.code,
-            source_map: printed.map.unwrap(),
-            original_source_map: original_map,
-            transform_status: None,
-            literals_result: None,
-        })
-    });
+    let print_result = compiler.print(&program, print_args);
+
+    print_result.map(|printed| RewrittenOutput {
+        code: printed.code,
+        source_map: printed.map.unwrap(),
+        original_source_map: original_map,
+        transform_status: None,
+        literals_result: None,
+        location_files: None,
+        sites: None,
+    })
 }
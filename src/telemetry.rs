@@ -16,16 +16,19 @@ pub enum TelemetryVerbosity {
 
 impl TelemetryVerbosity {
     pub fn parse(optional_value: Option<String>) -> Self {
-        if let Some(value) = optional_value {
-            return match value.to_uppercase().as_str() {
-                "OFF" => TelemetryVerbosity::Off,
-                "MANDATORY" => TelemetryVerbosity::Mandatory,
-                "INFORMATION" => TelemetryVerbosity::Information,
-                "DEBUG" => TelemetryVerbosity::Debug,
-                _ => TelemetryVerbosity::Information,
-            };
+        optional_value
+            .and_then(|value| TelemetryVerbosity::try_parse(&value))
+            .unwrap_or(TelemetryVerbosity::Information)
+    }
+
+    pub fn try_parse(value: &str) -> Option<Self> {
+        match value.to_uppercase().as_str() {
+            "OFF" => Some(TelemetryVerbosity::Off),
+            "MANDATORY" => Some(TelemetryVerbosity::Mandatory),
+            "INFORMATION" => Some(TelemetryVerbosity::Information),
+            "DEBUG" => Some(TelemetryVerbosity::Debug),
+            _ => None,
         }
-        TelemetryVerbosity::Information
     }
 }
 
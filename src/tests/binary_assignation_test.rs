@@ -88,4 +88,36 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_member_assignation() -> Result<(), String> {
+        let original_code = "{a.b += c;}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("a.b = (__datadog_test_0 = a.b, _ddiast.plusOperator(__datadog_test_0 + c, __datadog_test_0, c));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_member_assignation_evaluates_target_once() -> Result<(), String> {
+        let original_code = "{getObj().b += c; a[key()] += c; this[k] += c;}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = getObj()).b = (__datadog_test_1 = __datadog_test_0.b, _ddiast.plusOperator(__datadog_test_1 + c, __datadog_test_1, c));");
+        assert_that(&rewritten.code).contains("a[__datadog_test_0 = key()] = (__datadog_test_1 = a[__datadog_test_0], _ddiast.plusOperator(__datadog_test_1 + c, __datadog_test_1, c));");
+        assert_that(&rewritten.code).contains("this[k] = (__datadog_test_0 = this[k], _ddiast.plusOperator(__datadog_test_0 + c, __datadog_test_0, c));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_logical_assignation() -> Result<(), String> {
+        let original_code =
+            "{a ||= b + c; a.b ??= `${b}${c}`; a[key()] &&= b.concat(c);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("a ||= _ddiast.plusOperator(b + c, b, c);");
+        assert_that(&rewritten.code).contains("a.b ??= (__datadog_test_0 = b, __datadog_test_1 = c, _ddiast.tplOperator(`${__datadog_test_0}${__datadog_test_1}`, __datadog_test_0, __datadog_test_1));");
+        assert_that(&rewritten.code).contains("a[key()] &&= (__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.concat, __datadog_test_2 = c, _ddiast.stringConcat(");
+        Ok(())
+    }
 }
@@ -6,7 +6,7 @@
 #[cfg(test)]
 mod tests {
 
-    use speculoos::{assert_that, prelude::ContainingIntoIterAssertions, string::StrAssertions};
+    use speculoos::{assert_that, string::StrAssertions};
 
     use anyhow::Error;
 
@@ -186,10 +186,11 @@ mod tests {
     fn test_match_declared_variables_same_block() -> Result<(), String> {
         let original_code = "{const __datadog_test_0 = 666; const c = a + b();}".to_string();
         let js_file = "test.js".to_string();
-        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string());
-        assert!(&rewritten.is_err());
-        assert_that!(&rewritten.err()).contains(
-            "Cancelling test.js file rewrite. Reason: Variable name duplicated".to_string(),
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "let __datadog_test_1, __datadog_test_2;
+    const __datadog_test_0 = 666;
+    const c = (__datadog_test_1 = a, __datadog_test_2 = b(), _ddiast.plusOperator(__datadog_test_1 + __datadog_test_2, __datadog_test_1, __datadog_test_2));",
         );
         Ok(())
     }
@@ -200,10 +201,25 @@ mod tests {
             "{const __datadog_test_0 = 666; function z(){const c = a + b();}}{const d = e + f()}"
                 .to_string();
         let js_file = "test.js".to_string();
-        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string());
-        assert!(&rewritten.is_err());
-        assert_that!(&rewritten.err()).contains(
-            "Cancelling test.js file rewrite. Reason: Variable name duplicated".to_string(),
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "function z() {
+        let __datadog_test_0, __datadog_test_1;
+        const c = (__datadog_test_0 = a, __datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_used_variable_child_block() -> Result<(), String> {
+        let original_code =
+            "{const __datadog_test_0 = 666; function z(){const c = a + b() + __datadog_test_0;}}"
+                .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "function z() {
+        let __datadog_test_1, __datadog_test_2, __datadog_test_3;",
         );
         Ok(())
     }
@@ -212,10 +228,11 @@ mod tests {
     fn test_match_declared_function_param_block() -> Result<(), String> {
         let original_code = "{function z(__datadog_test_0){const c = a + b();}}".to_string();
         let js_file = "test.js".to_string();
-        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string());
-        assert!(&rewritten.is_err());
-        assert_that!(&rewritten.err()).contains(
-            "Cancelling test.js file rewrite. Reason: Variable name duplicated".to_string(),
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "function z(__datadog_test_0) {
+        let __datadog_test_1, __datadog_test_2;
+        const c = (__datadog_test_1 = a, __datadog_test_2 = b(), _ddiast.plusOperator(__datadog_test_1 + __datadog_test_2, __datadog_test_1, __datadog_test_2));",
         );
         Ok(())
     }
@@ -225,11 +242,40 @@ mod tests {
         let original_code =
             "{const a = b + c(); function z(__datadog_test_0){const d = e + f;}}{const d = e + f()}".to_string();
         let js_file = "test.js".to_string();
-        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string());
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "{
+    let __datadog_test_1, __datadog_test_2;
+    const a = (__datadog_test_1 = b, __datadog_test_2 = c(), _ddiast.plusOperator(__datadog_test_1 + __datadog_test_2, __datadog_test_1, __datadog_test_2));",
+        );
+        assert_that(&rewritten.code).contains(
+            "{
+    let __datadog_test_0, __datadog_test_1;
+    const d = (__datadog_test_0 = e, __datadog_test_1 = f(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));",
+        );
+        Ok(())
+    }
 
-        assert!(&rewritten.is_err());
-        assert_that!(&rewritten.err()).contains(
-            "Cancelling test.js file rewrite. Reason: Variable name duplicated".to_string(),
+    #[test]
+    fn test_match_declared_catch_param_block() -> Result<(), String> {
+        let original_code = "try { x() } catch (__datadog_test_0) { y = a + b() }".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "catch (__datadog_test_0) {
+    let __datadog_test_1, __datadog_test_2;",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_declared_arrow_param_block() -> Result<(), String> {
+        let original_code = "{const g = (__datadog_test_0) => a + b();}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains(
+            "const g = (__datadog_test_0)=>{
+        let __datadog_test_1, __datadog_test_2;",
         );
         Ok(())
     }
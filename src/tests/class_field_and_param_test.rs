@@ -0,0 +1,154 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::tests::rewrite_js;
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_default_param_does_not_share_enclosing_vars() -> Result<(), String> {
+        let original_code = "function f(q = 'x' + y()) { return q }
+const r = a + f();"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("var __datadog_test_0, __datadog_test_1;
+function f(q = ((__datadog_test_0)=>(__datadog_test_0 = y(), _ddiast.plusOperator('x' + __datadog_test_0, 'x', __datadog_test_0)))()) {
+    return q;
+}
+const r = (__datadog_test_0 = a, __datadog_test_1 = f(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_param_in_block() -> Result<(), String> {
+        let original_code = "{function f(q = 'x' + y) {}}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("function f(q = _ddiast.plusOperator('x' + y, 'x', y)) {}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_params_of_different_functions() -> Result<(), String> {
+        let original_code = "{
+const f = function(a = b + c()) {};
+const g = (a = b + c()) => a;
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("{
+    const f = function(a = ((__datadog_test_0, __datadog_test_1)=>(__datadog_test_0 = b, __datadog_test_1 = c(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1)))()) {};
+    const g = (a = ((__datadog_test_0, __datadog_test_1)=>(__datadog_test_0 = b, __datadog_test_1 = c(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1)))())=>{
+        return a;
+    };");
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrow_default_param_and_body() -> Result<(), String> {
+        let original_code = "{const g = (a = b + c()) => a + d();}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("{
+    const g = (a = ((__datadog_test_0, __datadog_test_1)=>(__datadog_test_0 = b, __datadog_test_1 = c(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1)))())=>{
+        let __datadog_test_0, __datadog_test_1;
+        return (__datadog_test_0 = a, __datadog_test_1 = d(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));
+    };");
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_fields() -> Result<(), String> {
+        let original_code = "class A {
+    url = base + this.path();
+    #p = `${a()}`;
+    static s = b.concat(c);
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("class A {
+    url = ((__datadog_test_0, __datadog_test_1)=>(__datadog_test_0 = base, __datadog_test_1 = this.path(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1)))();
+    #p = ((__datadog_test_0)=>(__datadog_test_0 = a(), _ddiast.tplOperator(`${__datadog_test_0}`, __datadog_test_0)))();
+    static s = ((__datadog_test_0, __datadog_test_1, __datadog_test_2)=>(__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.concat, __datadog_test_2 = c, _ddiast.stringConcat(__datadog_test_1.call(__datadog_test_0, __datadog_test_2), __datadog_test_1, __datadog_test_0, __datadog_test_2)))();
+}\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_computed_key_uses_enclosing_vars() -> Result<(), String> {
+        let original_code = "{class A { [k + l()] = 1 }}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("let __datadog_test_0, __datadog_test_1;
+    class A {
+        [(__datadog_test_0 = k, __datadog_test_1 = l(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1))] = 1;
+    }");
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_static_block() -> Result<(), String> {
+        let original_code = "class A { static { const x = a + b(); } }".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("static{
+        let __datadog_test_0, __datadog_test_1;
+        const x = (__datadog_test_0 = a, __datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));
+    }");
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_constructor_and_method_params() -> Result<(), String> {
+        let original_code = "{class A {
+    constructor(p = a + b()) {}
+    m(p = `${c()}`) {}
+}}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("{
+    class A {
+        constructor(p = ((__datadog_test_0, __datadog_test_1)=>(__datadog_test_0 = a, __datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1)))()){}
+        m(p = ((__datadog_test_0)=>(__datadog_test_0 = c(), _ddiast.tplOperator(`${__datadog_test_0}`, __datadog_test_0)))()) {}
+    }");
+        Ok(())
+    }
+
+    #[test]
+    fn test_class_field_used_variable() -> Result<(), String> {
+        let original_code = "class A { a = __datadog_test_0 + b() }".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("class A {
+    a = ((__datadog_test_1, __datadog_test_2)=>(__datadog_test_1 = __datadog_test_0, __datadog_test_2 = b(), _ddiast.plusOperator(__datadog_test_1 + __datadog_test_2, __datadog_test_1, __datadog_test_2)))();");
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_param_skips_param_names() -> Result<(), String> {
+        let original_code = "function f(q = a + b(), __datadog_test_1) {}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("function f(q = ((__datadog_test_0, __datadog_test_2)=>(__datadog_test_0 = a, __datadog_test_2 = b(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_2, __datadog_test_0, __datadog_test_2)))(), __datadog_test_1) {}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_param_of_recursive_function() -> Result<(), String> {
+        let original_code =
+            "function f(n, s = String(n) + (n ? f(n - 1) : '')) { return s }".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("function f(n, s = ((__datadog_test_0, __datadog_test_1)=>(__datadog_test_0 = String(n), __datadog_test_1 = (n ? f(n - 1) : ''), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1)))()) {
+    return s;
+}\n".to_string());
+        Ok(())
+    }
+}
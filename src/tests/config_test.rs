@@ -0,0 +1,479 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{ConfigError, RewriterConfig},
+        rewriter::SyntaxMode,
+        telemetry::TelemetryVerbosity,
+        visitor::csi_methods::CsiMethodKind,
+    };
+    use serde_json::Value;
+    use speculoos::{assert_that, option::OptionAssertions, prelude::BooleanAssertions};
+
+    fn parse_config(json: &str) -> Result<RewriterConfig, String> {
+        serde_json::from_str::<Value>(json)
+            .map_err(|e| e.to_string())
+            .and_then(|value| RewriterConfig::from_value(value).map_err(|e| e.to_string()))
+    }
+
+    fn get_config_errors(json: &str) -> Result<Vec<ConfigError>, String> {
+        let value = serde_json::from_str::<Value>(json).map_err(|e| e.to_string())?;
+        match RewriterConfig::from_value(value) {
+            Ok(_) => Err("Config expected to be invalid".to_string()),
+            Err(errors) => Ok(errors.errors),
+        }
+    }
+
+    fn config_error(key: &str, message: &str) -> ConfigError {
+        ConfigError {
+            key: key.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_config_defaults() -> Result<(), String> {
+        let config = parse_config("{}")?.to_config();
+
+        assert_that(&config.chain_source_map).is_false();
+        assert_that(&config.print_comments).is_false();
+        assert_that(&config.literals).is_true();
+        assert_that(&config.verbosity).is_equal_to(TelemetryVerbosity::Information);
+        assert_that(&config.local_var_prefix.len()).is_equal_to(6);
+        assert_that(&config.csi_methods.methods.is_empty()).is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_camel_case_fields() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "chainSourceMap": true,
+                "comments": true,
+                "localVarPrefix": "test",
+                "telemetryVerbosity": "DEBUG",
+                "literals": false
+            }"#,
+        )?
+        .to_config();
+
+        assert_that(&config.chain_source_map).is_true();
+        assert_that(&config.print_comments).is_true();
+        assert_that(&config.literals).is_false();
+        assert_that(&config.verbosity).is_equal_to(TelemetryVerbosity::Debug);
+        assert_that(&config.local_var_prefix).is_equal_to("test".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_csi_methods() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "csiMethods": [
+                    { "src": "plusOperator", "operator": true },
+                    { "src": "substring", "dst": "stringSubstring" },
+                    { "src": "trim", "allowedWithoutCallee": true }
+                ]
+            }"#,
+        )?
+        .to_config();
+
+        let csi_methods = &config.csi_methods;
+        assert_that(&csi_methods.methods.len()).is_equal_to(3);
+        assert_that(&csi_methods.plus_operator_is_enabled()).is_true();
+        assert_that(&csi_methods.get("substring").map(|m| m.dst.clone()))
+            .is_some()
+            .is_equal_to("stringSubstring".to_string());
+        assert_that(&csi_methods.get("trim").map(|m| m.allowed_without_callee))
+            .is_some()
+            .is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_null() -> Result<(), String> {
+        let config = parse_config("null")?.to_config();
+
+        assert_that(&config.literals).is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_not_an_object() -> Result<(), String> {
+        let errors = get_config_errors("[1, 2]")?;
+
+        assert_that(&errors).is_equal_to(vec![config_error("", "expected an object")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_unknown_keys() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{ "unknown": true, "csiMethods": [{ "src": "trim", "unknownMethodKey": 1 }] }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![
+            config_error("csiMethods[0].unknownMethodKey", "unknown key"),
+            config_error("unknown", "unknown key"),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_wrong_types() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{
+                "comments": "true",
+                "localVarPrefix": 1,
+                "csiMethods": [{ "src": 1, "operator": "true" }, "trim"]
+            }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![
+            config_error("comments", "expected a boolean"),
+            config_error("csiMethods[0].operator", "expected a boolean"),
+            config_error("csiMethods[0].src", "expected a string"),
+            config_error("csiMethods[1]", "expected an object"),
+            config_error("localVarPrefix", "expected a string"),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_missing_src() -> Result<(), String> {
+        let errors = get_config_errors(r#"{ "csiMethods": [{ "dst": "trim" }] }"#)?;
+
+        assert_that(&errors).is_equal_to(vec![config_error(
+            "csiMethods[0].src",
+            "missing required key",
+        )]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_unknown_operator() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{ "csiMethods": [{ "src": "minusOperator", "operator": true }] }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![config_error(
+            "csiMethods[0].src",
+            "unknown operator 'minusOperator', expected one of plusOperator, tplOperator, propertyWriteOperator, taggedTplOperator",
+        )]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_csi_method_kind() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "csiMethods": [
+                    { "src": "exec", "kind": "before" },
+                    { "src": "query", "kind": "around" },
+                    { "src": "trim" }
+                ]
+            }"#,
+        )?
+        .to_config();
+
+        let csi_methods = &config.csi_methods;
+        assert_that(&csi_methods.get("exec").map(|m| m.kind))
+            .is_some()
+            .is_equal_to(CsiMethodKind::Before);
+        assert_that(&csi_methods.get("query").map(|m| m.kind))
+            .is_some()
+            .is_equal_to(CsiMethodKind::Around);
+        assert_that(&csi_methods.get("trim").map(|m| m.kind))
+            .is_some()
+            .is_equal_to(CsiMethodKind::After);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_invalid_csi_method_kind() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{
+                "csiMethods": [
+                    { "src": "exec", "kind": "instead" },
+                    { "src": "plusOperator", "operator": true, "kind": "before" },
+                    { "src": "URL", "kind": "constructor" },
+                    { "src": "URL", "kind": "constructor" }
+                ]
+            }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![
+            config_error(
+                "csiMethods[0].kind",
+                "unknown kind 'instead', expected one of after, before, around, constructor, propertyRead",
+            ),
+            config_error("csiMethods[1].kind", "operators only support kind 'after'"),
+            config_error("csiMethods[3]", "duplicate src 'URL'"),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_csi_method_constructor() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "csiMethods": [
+                    { "src": "String", "dst": "stringConstructor", "kind": "constructor" },
+                    { "src": "String", "allowedWithoutCallee": true }
+                ]
+            }"#,
+        )?
+        .to_config();
+
+        let csi_methods = &config.csi_methods;
+        assert_that(
+            &csi_methods
+                .get_constructor("String", None)
+                .map(|m| m.dst.clone()),
+        )
+        .is_some()
+        .is_equal_to("stringConstructor".to_string());
+        assert_that(&csi_methods.get("String").map(|m| m.kind))
+            .is_some()
+            .is_equal_to(CsiMethodKind::After);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_csi_method_receiver() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "csiMethods": [
+                    { "src": "join", "dst": "pathJoin", "receiver": "node:path" },
+                    { "src": "join" }
+                ]
+            }"#,
+        )?
+        .to_config();
+
+        let csi_methods = &config.csi_methods;
+        assert_that(
+            &csi_methods
+                .get_qualified("join", "path")
+                .map(|m| m.dst.clone()),
+        )
+        .is_some()
+        .is_equal_to("pathJoin".to_string());
+        assert_that(&csi_methods.get("join").map(|m| m.dst.clone()))
+            .is_some()
+            .is_equal_to("join".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_invalid_csi_method_receiver() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{
+                "csiMethods": [
+                    { "src": "exec", "receiver": "child_process" },
+                    { "src": "exec", "receiver": "child_process" },
+                    { "src": "join", "receiver": "" },
+                    { "src": "plusOperator", "operator": true, "receiver": "path" }
+                ]
+            }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![
+            config_error(
+                "csiMethods[1]",
+                "duplicate src 'exec' for receiver 'child_process'",
+            ),
+            config_error("csiMethods[2].receiver", "expected a non empty string"),
+            config_error(
+                "csiMethods[3].receiver",
+                "operators do not support receiver",
+            ),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_csi_method_property_read() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "csiMethods": [
+                    { "src": "queryRead", "kind": "propertyRead", "receiverPattern": "req.query" }
+                ]
+            }"#,
+        )?
+        .to_config();
+
+        let csi_methods = &config.csi_methods;
+        assert_that(
+            &csi_methods
+                .get_property_read("req.query")
+                .map(|m| m.dst.clone()),
+        )
+        .is_some()
+        .is_equal_to("queryRead".to_string());
+        assert_that(&csi_methods.get("queryRead")).is_none();
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_invalid_csi_method_property_read() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{
+                "csiMethods": [
+                    { "src": "queryRead", "kind": "propertyRead" },
+                    { "src": "headersRead", "kind": "propertyRead", "receiverPattern": "req..headers" },
+                    { "src": "bodyRead", "kind": "propertyRead", "receiverPattern": "req[body]" },
+                    { "src": "exec", "receiverPattern": "req.query" }
+                ]
+            }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![
+            config_error(
+                "csiMethods[0].receiverPattern",
+                "missing required key for kind 'propertyRead'",
+            ),
+            config_error(
+                "csiMethods[1].receiverPattern",
+                "expected a dotted path of identifiers like req.query",
+            ),
+            config_error(
+                "csiMethods[2].receiverPattern",
+                "expected a dotted path of identifiers like req.query",
+            ),
+            config_error(
+                "csiMethods[3].receiverPattern",
+                "only supported by kind 'propertyRead'",
+            ),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_duplicate_src() -> Result<(), String> {
+        let errors = get_config_errors(
+            r#"{ "csiMethods": [{ "src": "trim" }, { "src": "substring" }, { "src": "trim" }] }"#,
+        )?;
+
+        assert_that(&errors)
+            .is_equal_to(vec![config_error("csiMethods[2]", "duplicate src 'trim'")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_property_reads_with_same_src() -> Result<(), String> {
+        let config = parse_config(
+            r#"{
+                "csiMethods": [
+                    { "src": "userInput", "kind": "propertyRead", "receiverPattern": "req.query" },
+                    { "src": "userInput", "kind": "propertyRead", "receiverPattern": "req.body" },
+                    { "src": "userInput" }
+                ]
+            }"#,
+        )?
+        .to_config();
+
+        assert_that(&config.csi_methods.methods.len()).is_equal_to(3);
+        assert_that(
+            &config
+                .csi_methods
+                .get_property_read("req.body")
+                .map(|m| m.dst.clone()),
+        )
+        .is_equal_to(Some("userInput".to_string()));
+
+        let errors = get_config_errors(
+            r#"{
+                "csiMethods": [
+                    { "src": "userInput", "kind": "propertyRead", "receiverPattern": "req.query" },
+                    { "src": "userInput", "kind": "propertyRead", "receiverPattern": "req.query" }
+                ]
+            }"#,
+        )?;
+
+        assert_that(&errors).is_equal_to(vec![config_error(
+            "csiMethods[1]",
+            "duplicate src 'userInput' for receiver pattern 'req.query'",
+        )]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_invalid_local_var_prefix() -> Result<(), String> {
+        let errors = get_config_errors(r#"{ "localVarPrefix": "test-prefix" }"#)?;
+
+        assert_that(&errors).is_equal_to(vec![config_error(
+            "localVarPrefix",
+            "expected a non empty string of [A-Za-z0-9_$] characters",
+        )]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_unknown_telemetry_verbosity() -> Result<(), String> {
+        let errors = get_config_errors(r#"{ "telemetryVerbosity": "unknown" }"#)?;
+
+        assert_that(&errors).is_equal_to(vec![config_error(
+            "telemetryVerbosity",
+            "unknown telemetry verbosity 'unknown'",
+        )]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_syntax() -> Result<(), String> {
+        let config = parse_config(r#"{ "syntax": "typescript", "stripTypes": true }"#)?.to_config();
+
+        assert_that(&config.syntax).is_equal_to(SyntaxMode::Typescript);
+        assert_that(&config.strip_types).is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_unknown_syntax() -> Result<(), String> {
+        let errors = get_config_errors(r#"{ "syntax": "coffeescript", "stripTypes": "yes" }"#)?;
+
+        assert_that(&errors).is_equal_to(vec![
+            config_error("stripTypes", "expected a boolean"),
+            config_error("syntax", "unknown syntax 'coffeescript'"),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_lenient() -> Result<(), String> {
+        let config = parse_config(
+            r#"{ "lenient": true, "unknown": true, "telemetryVerbosity": "unknown" }"#,
+        )?
+        .to_config();
+
+        assert_that(&config.verbosity).is_equal_to(TelemetryVerbosity::Information);
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_lenient_wrong_types_fallback_to_default() -> Result<(), String> {
+        let config =
+            parse_config(r#"{ "lenient": true, "localVarPrefix": "test", "comments": 1 }"#)?
+                .to_config();
+
+        assert_that(&config.local_var_prefix).is_not_equal_to("test".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_errors_message() -> Result<(), String> {
+        let message = parse_config(r#"{ "comments": 1, "unknown": true }"#)
+            .err()
+            .unwrap_or_default();
+
+        assert_that(&message).is_equal_to(
+            "Invalid rewriter config: comments: expected a boolean, unknown: unknown key"
+                .to_string(),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,193 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewriter::generate_prefix_stmts,
+        telemetry::Telemetry,
+        tests::{
+            assert_not_modified, csi_kind_from_str, get_default_config, rewrite_js_with_config,
+            rewrite_js_with_csi_methods,
+        },
+        visitor::csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_constructor() -> Result<(), String> {
+        let original_code = "const u = new URL(path, base);".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str(
+            "URL",
+            Some("urlConstructor"),
+            CsiMethodKind::Constructor,
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const u = (__datadog_test_0 = path, __datadog_test_1 = base, _ddiast.urlConstructor(new URL(__datadog_test_0, __datadog_test_1), URL, __datadog_test_0, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_without_arguments() -> Result<(), String> {
+        let original_code = "const r = new RegExp;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str(
+            "RegExp",
+            Some("regExpConstructor"),
+            CsiMethodKind::Constructor,
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code)
+            .contains("const r = _ddiast.regExpConstructor(new RegExp, RegExp);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_spread_arguments() -> Result<(), String> {
+        let original_code = "const r = new RegExp(...args);".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str(
+            "RegExp",
+            Some("regExpConstructor"),
+            CsiMethodKind::Constructor,
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.regExpConstructor(new RegExp(...__datadog_test_0), RegExp, ...__datadog_test_0));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_nested() -> Result<(), String> {
+        let original_code = "const u = new URL(new URL(a));".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str(
+            "URL",
+            Some("urlConstructor"),
+            CsiMethodKind::Constructor,
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const u = (__datadog_test_1 = (__datadog_test_0 = a, _ddiast.urlConstructor(new URL(__datadog_test_0), URL, __datadog_test_0)), _ddiast.urlConstructor(new URL(__datadog_test_1), URL, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_with_receiver() -> Result<(), String> {
+        let original_code = "const vm = require('vm');
+const { Script } = require('node:vm');
+new vm.Script(code);
+new Script(code);
+new other.Script(code);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![CsiMethod::new(
+            "Script".to_string(),
+            Some("vmScript".to_string()),
+            false,
+            false,
+            CsiMethodKind::Constructor,
+            Some("vm".to_string()),
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = vm.Script, __datadog_test_1 = code, _ddiast.vmScript(new __datadog_test_0(__datadog_test_1), __datadog_test_0, __datadog_test_1));");
+        assert_that(&rewritten.code).contains(
+            "(__datadog_test_0 = code, _ddiast.vmScript(new Script(__datadog_test_0), Script, __datadog_test_0));",
+        );
+        assert_that(&rewritten.code).contains("new other.Script(code);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_not_matching_calls() -> Result<(), String> {
+        let original_code = "const a = URL(b);
+const c = d.URL(e);
+const f = new Other(g);
+const h = new (getUrl())(i);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str(
+            "URL",
+            Some("urlConstructor"),
+            CsiMethodKind::Constructor,
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_and_method_with_same_src() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.csi_methods = CsiMethods::new(&[
+            CsiMethod::new(
+                "String".to_string(),
+                None,
+                false,
+                true,
+                CsiMethodKind::After,
+                None,
+            ),
+            csi_kind_from_str("String", None, CsiMethodKind::Constructor),
+        ]);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, false, false);
+        let rewritten = rewrite_js_with_config(
+            "const a = new String(b);
+const c = String(d);"
+                .to_string(),
+            &config,
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.StringConstructor(new String(");
+        assert_that(&rewritten.code).contains("_ddiast.String(String(");
+        assert_that(&rewritten.code).contains(
+            "globals._ddiast = globals._ddiast || {
+        String: noop,
+        StringConstructor: noop
+    };",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_constructor_telemetry_tag() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.csi_methods = CsiMethods::new(&[csi_kind_from_str(
+            "URL",
+            Some("urlConstructor"),
+            CsiMethodKind::Constructor,
+        )]);
+        let rewritten = rewrite_js_with_config(
+            "const u = new URL(a); const v = new URL(b);".to_string(),
+            &config,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let transform_status = rewritten.transform_status.ok_or("missing status")?;
+        let propagation_debug = transform_status
+            .telemetry
+            .get_propagation_debug()
+            .ok_or("missing debug telemetry")?;
+        assert_that(&propagation_debug.get("URL")).is_equal_to(Some(&2));
+        Ok(())
+    }
+}
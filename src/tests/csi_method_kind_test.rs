@@ -0,0 +1,207 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewriter::generate_prefix_stmts,
+        tests::{
+            csi_kind_from_str, get_default_config, rewrite_js_with_config,
+            rewrite_js_with_csi_methods,
+        },
+        visitor::csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_before_member_call() -> Result<(), String> {
+        let original_code = "{const a = b.exec(c, 'x');}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str("exec", None, CsiMethodKind::Before)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("let __datadog_test_0, __datadog_test_1, __datadog_test_2, __datadog_test_3;
+    const a = (__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = c, __datadog_test_3 = _ddiast.execBefore(__datadog_test_1, __datadog_test_0, [
+        __datadog_test_2,
+        'x'
+    ]), __datadog_test_1.apply(__datadog_test_0, __datadog_test_3));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_before_member_call_without_args() -> Result<(), String> {
+        let original_code = "{const a = b.c.exec();}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str("exec", None, CsiMethodKind::Before)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = b.c, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = _ddiast.execBefore(__datadog_test_1, __datadog_test_0, []), __datadog_test_1.apply(__datadog_test_0, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_before_alone_method() -> Result<(), String> {
+        let original_code = "{const a = aloneMethod(c);}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![CsiMethod::new(
+            "aloneMethod".to_string(),
+            None,
+            false,
+            true,
+            CsiMethodKind::Before,
+            None,
+        )];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = c, __datadog_test_1 = _ddiast.aloneMethodBefore(aloneMethod, undefined, [
+        __datadog_test_0
+    ]), aloneMethod.apply(undefined, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_before_prototype_call_this_spread() -> Result<(), String> {
+        let original_code = "{const a = Cmd.prototype.exec.call(...b);}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str("exec", None, CsiMethodKind::Before)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "const a = (__datadog_test_0 = Cmd.prototype.exec, __datadog_test_1 = [
+        ...b
+    ], __datadog_test_2 = _ddiast.execBefore(__datadog_test_0, __datadog_test_1[0], [
+        ...__datadog_test_1.slice(1)
+    ]), __datadog_test_0.apply(__datadog_test_1[0], __datadog_test_2));",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_before_nested_calls() -> Result<(), String> {
+        let original_code = "{const a = b.exec(c.exec(d));}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str("exec", None, CsiMethodKind::Before)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_4 = b, __datadog_test_5 = __datadog_test_4.exec, __datadog_test_6 = (__datadog_test_0 = c, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = d, __datadog_test_3 = _ddiast.execBefore(__datadog_test_1, __datadog_test_0, [
+        __datadog_test_2
+    ]), __datadog_test_1.apply(__datadog_test_0, __datadog_test_3)), __datadog_test_7 = _ddiast.execBefore(__datadog_test_5, __datadog_test_4, [
+        __datadog_test_6
+    ]), __datadog_test_5.apply(__datadog_test_4, __datadog_test_7));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_around_member_call() -> Result<(), String> {
+        let original_code = "{const a = b.exec(c, 'x');}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str("exec", None, CsiMethodKind::Around)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = c, __datadog_test_3 = _ddiast.execBefore(__datadog_test_1, __datadog_test_0, [
+        __datadog_test_2,
+        'x'
+    ]), _ddiast.exec(__datadog_test_1.apply(__datadog_test_0, __datadog_test_3), __datadog_test_1, __datadog_test_0, ...__datadog_test_3));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_around_prototype_apply() -> Result<(), String> {
+        let original_code = "{const a = Cmd.prototype.exec.apply(b, [c]);}".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_kind_from_str("exec", None, CsiMethodKind::Around)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = b, __datadog_test_1 = Cmd.prototype.exec, __datadog_test_2 = c, __datadog_test_3 = _ddiast.execBefore(__datadog_test_1, __datadog_test_0, [
+        __datadog_test_2
+    ]), _ddiast.exec(__datadog_test_1.apply(__datadog_test_0, __datadog_test_3), __datadog_test_1, __datadog_test_0, ...__datadog_test_3));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_before_site_id_and_location() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.csi_methods =
+            CsiMethods::new(&[csi_kind_from_str("exec", None, CsiMethodKind::Before)]);
+        config.site_ids = true;
+        config.call_site_locations = true;
+
+        let rewritten = rewrite_js_with_config("const a = b.exec();".to_string(), &config)
+            .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.execBefore(\"0:1:11\", 0, __datadog_test_1, __datadog_test_0, []), __datadog_test_1.apply(__datadog_test_0, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_before_prefix() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.csi_methods = CsiMethods::new(&[
+            CsiMethod::new(
+                "exec".to_string(),
+                None,
+                false,
+                false,
+                CsiMethodKind::Before,
+                None,
+            ),
+            CsiMethod::new(
+                "query".to_string(),
+                None,
+                false,
+                false,
+                CsiMethodKind::Around,
+                None,
+            ),
+            CsiMethod::new(
+                "trim".to_string(),
+                None,
+                false,
+                false,
+                CsiMethodKind::After,
+                None,
+            ),
+        ]);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, false, false);
+
+        let rewritten = rewrite_js_with_config("const a = b.trim();".to_string(), &config)
+            .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "const noop = (res)=>res, noopBefore = (fn, self, args)=>args;
+    globals._ddiast = globals._ddiast || {
+        execBefore: noopBefore,
+        queryBefore: noopBefore,
+        query: noop,
+        trim: noop
+    };",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_with_shared_hook() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, false, false);
+        let rewritten = rewrite_js_with_config("const a = b.trim();".to_string(), &config)
+            .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("stringTrim: noop,\n        stringConcat: noop");
+        Ok(())
+    }
+}
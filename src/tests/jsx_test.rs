@@ -0,0 +1,107 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewrite_error::{RewriteError, RewriteErrorCode},
+        rewriter::RewrittenOutput,
+        tests::get_default_config,
+        transform::transform_status::Status,
+        util::DefaultFileReader,
+    };
+    use speculoos::{assert_that, option::OptionAssertions};
+
+    fn rewrite_jsx(code: &str, file: &str, jsx: bool) -> Result<RewrittenOutput, RewriteError> {
+        let mut config = get_default_config(false);
+        config.jsx = jsx;
+        crate::rewriter::rewrite_js(code.to_string(), file, &config, &DefaultFileReader {})
+    }
+
+    #[test]
+    fn test_jsx_attributes_and_children() -> Result<(), String> {
+        let rewritten = rewrite_jsx(
+            "function C(p) { return <a href={'/user/' + p.id} title=\"t\">{`Hello ${p.name()}`}</a>; }",
+            "test.jsx",
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("function C(p) {
+    let __datadog_test_0;
+    return <a href={(__datadog_test_0 = p.id, _ddiast.plusOperator('/user/' + __datadog_test_0, '/user/', __datadog_test_0))} title=\"t\">{(__datadog_test_0 = p.name(), _ddiast.tplOperator(`Hello ${__datadog_test_0}`, __datadog_test_0))}</a>;
+}\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_jsx_fragment_member_element_and_spread() -> Result<(), String> {
+        let rewritten = rewrite_jsx(
+            "const C = (p) => <><b>{p.a.trim()}</b><Foo.Bar x={p.y + 'z'} {...p} /></>;",
+            "test.jsx",
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("const C = (p)=>{
+    let __datadog_test_0, __datadog_test_1;
+    return <><b>{(__datadog_test_0 = p.a, __datadog_test_1 = __datadog_test_0.trim, _ddiast.stringTrim(__datadog_test_1.call(__datadog_test_0), __datadog_test_1, __datadog_test_0))}</b><Foo.Bar x={(__datadog_test_0 = p.y, _ddiast.plusOperator(__datadog_test_0 + 'z', __datadog_test_0, 'z'))} {...p}/></>;
+};\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_jsx_no_expressions() -> Result<(), String> {
+        let rewritten = rewrite_jsx(
+            "const C = () => <div className=\"a\">text</div>;",
+            "test.jsx",
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.transform_status.map(|status| status.status))
+            .is_some()
+            .is_equal_to(Status::NotModified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tsx_event_handler() -> Result<(), String> {
+        let rewritten = rewrite_jsx(
+            "function C(p: {a: string}) { return <div onClick={() => p.a + p.b()}>{p.a + 'x'}</div>; }",
+            "test.tsx",
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("function C(p: {
+    a: string;
+}) {
+    let __datadog_test_0;
+    return <div onClick={()=>{
+        let __datadog_test_0, __datadog_test_1;
+        return (__datadog_test_0 = p.a, __datadog_test_1 = p.b(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));
+    }}>{(__datadog_test_0 = p.a, _ddiast.plusOperator(__datadog_test_0 + 'x', __datadog_test_0, 'x'))}</div>;
+}\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tsx_without_jsx_mode() -> Result<(), String> {
+        let rewritten = rewrite_jsx(
+            "function C(p: {a: string}) { return <div>{p.a + 'x'}</div>; }",
+            "test.tsx",
+            false,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.transform_status.map(|status| status.status))
+            .is_some()
+            .is_equal_to(Status::NotModified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_jsx_without_jsx_mode() {
+        let result = rewrite_jsx("const a = <a href={b + c}></a>;", "test.jsx", false);
+        assert_that(&result.err().map(|e| e.code))
+            .is_some()
+            .is_equal_to(RewriteErrorCode::ParseError);
+    }
+}
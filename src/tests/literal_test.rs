@@ -2,7 +2,6 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
  **/
-
 #[cfg(test)]
 mod tests {
     use speculoos::assert_that;
@@ -26,7 +25,7 @@ mod tests {
         assert_that(&rewritten.literals_result.is_some());
         let result = rewritten.literals_result.unwrap();
 
-        let literal_info = result.literals.get(0).unwrap();
+        let literal_info = result.literals.first().unwrap();
 
         assert_that(&literal_info.locations.len()).is_equal_to(1);
 
@@ -45,7 +44,7 @@ mod tests {
 
         assert_that(&rewritten.literals_result.is_some());
         let result = rewritten.literals_result.unwrap();
-        let literal_info = result.literals.get(0).unwrap();
+        let literal_info = result.literals.first().unwrap();
 
         assert_that(&literal_info.locations.len()).is_equal_to(1);
 
@@ -69,7 +68,7 @@ mod tests {
 
         assert_that(&rewritten.literals_result.is_some());
         let result = rewritten.literals_result.unwrap();
-        let literal_info = result.literals.get(0).unwrap();
+        let literal_info = result.literals.first().unwrap();
 
         assert_that(&literal_info.locations.len()).is_equal_to(2);
 
@@ -99,7 +98,7 @@ mod tests {
 
         assert_that(&rewritten.literals_result.is_some());
         let result = rewritten.literals_result.unwrap();
-        let literal_info = result.literals.get(0).unwrap();
+        let literal_info = result.literals.first().unwrap();
 
         assert_that(&literal_info.locations.len()).is_equal_to(1);
 
@@ -118,7 +117,7 @@ mod tests {
 
         assert_that(&rewritten.literals_result.is_some());
         let result = rewritten.literals_result.unwrap();
-        let literal_info = result.literals.get(0).unwrap();
+        let literal_info = result.literals.first().unwrap();
 
         assert_that(&literal_info.locations.len()).is_equal_to(1);
 
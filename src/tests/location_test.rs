@@ -0,0 +1,135 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        rewriter::generate_prefix_stmts,
+        tests::{
+            csi_op_from_str, get_default_config, get_test_resources_folder, rewrite_js_with_config,
+        },
+        util::DefaultFileReader,
+        visitor::csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
+    };
+    use speculoos::{assert_that, option::OptionAssertions, string::StrAssertions};
+
+    #[test]
+    fn test_location_plus_operator() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.call_site_locations = true;
+        let rewritten = rewrite_js_with_config("{const a = b + c;}".to_string(), &config)
+            .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code)
+            .contains("const a = _ddiast.plusOperator(\"0:1:12\", b + c, b, c);");
+        assert_that(&rewritten.location_files)
+            .is_some()
+            .is_equal_to(vec!["test.js".to_string()]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_template_literal() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.call_site_locations = true;
+        let rewritten =
+            rewrite_js_with_config("{\n  const a = `hello ${b}`;\n}".to_string(), &config)
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "_ddiast.tplOperator(\"0:2:13\", `hello ${__datadog_test_0}`, __datadog_test_0));",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_nested_hooks() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.call_site_locations = true;
+        let rewritten =
+            rewrite_js_with_config("{const a = b + c.substring(1);}".to_string(), &config)
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "_ddiast.stringSubstring(\"0:1:16\", __datadog_test_1.call(__datadog_test_0, 1)",
+        );
+        assert_that(&rewritten.code)
+            .contains("_ddiast.plusOperator(\"0:1:12\", __datadog_test_2 + __datadog_test_3");
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_prefix_noops() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.call_site_locations = true;
+        config.csi_methods = CsiMethods::new(&[
+            csi_op_from_str("plusOperator", None),
+            CsiMethod::new(
+                "exec".to_string(),
+                None,
+                false,
+                false,
+                CsiMethodKind::Before,
+                None,
+            ),
+        ]);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, true, false);
+        let rewritten = crate::rewriter::rewrite_js(
+            "const a = b + c;\nconst d = e.exec(a);".to_string(),
+            "test.js",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "const noop = (location, res)=>res, noopBefore = (location, fn, self, args)=>args;",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_disabled() -> Result<(), String> {
+        let source_map_reader = DefaultFileReader {};
+        let rewritten = crate::rewriter::rewrite_js(
+            "{const a = b + c;}".to_string(),
+            "test.js",
+            &get_default_config(false),
+            &source_map_reader,
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = _ddiast.plusOperator(b + c, b, c);");
+        assert_that(&rewritten.location_files).is_none();
+        Ok(())
+    }
+
+    #[test]
+    fn test_location_original_source_map() -> Result<(), String> {
+        let js_file = get_test_resources_folder()?
+            .join("sourcemap")
+            .join("StrUtil_external.js");
+        let code = fs::read_to_string(&js_file).map_err(|e| e.to_string())?;
+
+        let mut config = get_default_config(false);
+        config.call_site_locations = true;
+        let rewritten = crate::rewriter::rewrite_js(
+            code,
+            js_file.to_str().unwrap(),
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+
+        // StrUtil.js.map was generated for the original tsc output, not the reformatted file
+
+        assert_that(&rewritten.code).contains("_ddiast.plusOperator(\"0:2:3\", '-' + a, '-', a)");
+        assert_that(&rewritten.location_files)
+            .is_some()
+            .is_equal_to(vec!["StrUtil.ts".to_string()]);
+        Ok(())
+    }
+}
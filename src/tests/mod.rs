@@ -3,24 +3,42 @@
  * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
  **/
 use crate::{
-    rewriter::{Config, RewrittenOutput},
+    rewrite_error::RewriteError,
+    rewriter::{Config, ParserOptions, RewrittenOutput, SyntaxMode},
     telemetry::TelemetryVerbosity,
     transform::transform_status::Status,
     util::DefaultFileReader,
-    visitor::csi_methods::{CsiMethod, CsiMethods},
+    visitor::csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
 };
-use anyhow::Error;
 use speculoos::{assert_that, prelude::BooleanAssertions};
 use std::path::PathBuf;
 
 mod arrow_func_tests;
 mod binary_assignation_test;
 mod binary_expression_test;
+mod class_field_and_param_test;
+mod config_test;
+mod constructor_test;
+mod csi_method_kind_test;
+mod jsx_test;
 mod literal_test;
+mod location_test;
+mod parser_options_test;
+mod property_read_test;
+mod property_write_test;
+mod prototype_reference_test;
+mod receiver_test;
+mod rewrite_error_test;
+mod rewrite_options_test;
+mod site_test;
 mod source_map_test;
 mod string_method_test;
+mod symbol_table_test;
+mod tagged_template_test;
 mod telemetry_test;
 mod template_literal_test;
+mod top_level_test;
+mod typescript_test;
 
 fn get_test_resources_folder() -> Result<PathBuf, String> {
     std::env::current_dir()
@@ -28,7 +46,7 @@ fn get_test_resources_folder() -> Result<PathBuf, String> {
         .map_err(|e| e.to_string())
 }
 
-fn rewrite_js(code: String, file: String) -> Result<RewrittenOutput, Error> {
+fn rewrite_js(code: String, file: String) -> Result<RewrittenOutput, RewriteError> {
     let source_map_reader = DefaultFileReader {};
     crate::rewriter::rewrite_js(code, &file, &get_default_config(false), &source_map_reader)
 }
@@ -37,7 +55,7 @@ fn rewrite_js_with_telemetry_verbosity(
     code: String,
     file: String,
     verbosity: TelemetryVerbosity,
-) -> Result<RewrittenOutput, Error> {
+) -> Result<RewrittenOutput, RewriteError> {
     let source_map_reader = DefaultFileReader {};
     crate::rewriter::rewrite_js(
         code,
@@ -51,7 +69,7 @@ fn rewrite_js_with_csi_methods(
     code: String,
     file: String,
     csi_methods: &CsiMethods,
-) -> Result<RewrittenOutput, Error> {
+) -> Result<RewrittenOutput, RewriteError> {
     let source_map_reader = DefaultFileReader {};
     crate::rewriter::rewrite_js(
         code,
@@ -64,18 +82,24 @@ fn rewrite_js_with_csi_methods(
             verbosity: TelemetryVerbosity::Information,
             literals: false,
             file_prefix_code: Vec::new(),
+            syntax: SyntaxMode::Auto,
+            strip_types: false,
+            jsx: false,
+            parser_options: ParserOptions::default(),
+            call_site_locations: false,
+            site_ids: false,
         },
         &source_map_reader,
     )
 }
 
-fn rewrite_js_with_config(code: String, config: &Config) -> Result<RewrittenOutput, Error> {
+fn rewrite_js_with_config(code: String, config: &Config) -> Result<RewrittenOutput, RewriteError> {
     let source_map_reader = DefaultFileReader {};
     crate::rewriter::rewrite_js(code, "test.js", config, &source_map_reader)
 }
 
 fn get_default_csi_methods() -> CsiMethods {
-    let mut methods = vec![
+    let methods = vec![
         csi_op_from_str("plusOperator", None),
         csi_op_from_str("tplOperator", None),
         csi_from_str("substring", Some("stringSubstring")),
@@ -86,7 +110,7 @@ fn get_default_csi_methods() -> CsiMethods {
         csi_from_str("slice", None),
         csi_from_str("replace", None),
     ];
-    CsiMethods::new(&mut methods)
+    CsiMethods::new(&methods)
 }
 
 fn get_default_config(print_comments: bool) -> Config {
@@ -105,6 +129,12 @@ fn get_default_config_with_verbosity(
         verbosity,
         literals: false,
         file_prefix_code: Vec::new(),
+        syntax: SyntaxMode::Auto,
+        strip_types: false,
+        jsx: false,
+        parser_options: ParserOptions::default(),
+        call_site_locations: false,
+        site_ids: false,
     }
 }
 
@@ -117,6 +147,12 @@ fn get_chained_and_print_comments_config() -> Config {
         verbosity: TelemetryVerbosity::Debug,
         literals: false,
         file_prefix_code: Vec::new(),
+        syntax: SyntaxMode::Auto,
+        strip_types: false,
+        jsx: false,
+        parser_options: ParserOptions::default(),
+        call_site_locations: false,
+        site_ids: false,
     }
 }
 
@@ -129,23 +165,50 @@ fn get_literals_config() -> Config {
         verbosity: TelemetryVerbosity::Debug,
         literals: true,
         file_prefix_code: Vec::new(),
+        syntax: SyntaxMode::Auto,
+        strip_types: false,
+        jsx: false,
+        parser_options: ParserOptions::default(),
+        call_site_locations: false,
+        site_ids: false,
     }
 }
 
 fn csi_from_str(src: &str, dst: Option<&str>) -> CsiMethod {
-    let dst_string = match dst {
-        Some(str) => Some(String::from(str)),
-        None => None,
-    };
-    CsiMethod::new(String::from(src), dst_string, false, false)
+    csi_kind_from_str(src, dst, CsiMethodKind::After)
+}
+
+fn csi_kind_from_str(src: &str, dst: Option<&str>, kind: CsiMethodKind) -> CsiMethod {
+    let dst_string = dst.map(String::from);
+    CsiMethod::new(String::from(src), dst_string, false, false, kind, None)
+}
+
+fn csi_property_read_from_str(src: &str, receiver_pattern: &str) -> CsiMethod {
+    csi_kind_from_str(src, None, CsiMethodKind::PropertyRead)
+        .with_receiver_pattern(Some(String::from(receiver_pattern)))
+}
+
+fn csi_receiver_from_str(src: &str, dst: &str, receiver: &str) -> CsiMethod {
+    CsiMethod::new(
+        String::from(src),
+        Some(String::from(dst)),
+        false,
+        false,
+        CsiMethodKind::After,
+        Some(String::from(receiver)),
+    )
 }
 
 fn csi_op_from_str(src: &str, dst: Option<&str>) -> CsiMethod {
-    let dst_string = match dst {
-        Some(str) => Some(String::from(str)),
-        None => None,
-    };
-    CsiMethod::new(String::from(src), dst_string, true, false)
+    let dst_string = dst.map(String::from);
+    CsiMethod::new(
+        String::from(src),
+        dst_string,
+        true,
+        false,
+        CsiMethodKind::After,
+        None,
+    )
 }
 
 fn assert_not_modified(output: &RewrittenOutput) {
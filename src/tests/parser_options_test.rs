@@ -0,0 +1,273 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{ConfigError, RewriteOptions, RewriterConfig},
+        rewrite_error::{RewriteError, RewriteErrorCode},
+        rewriter::{Config, ModuleKind, ParserOptions, RewrittenOutput},
+        tests::{get_default_config, rewrite_js_with_config},
+        util::DefaultFileReader,
+    };
+    use serde_json::Value;
+    use speculoos::{assert_that, prelude::BooleanAssertions, string::StrAssertions};
+    use swc_ecma_ast::EsVersion;
+
+    fn parse_options(json: &str, config: &Config) -> Result<RewriteOptions, String> {
+        serde_json::from_str::<Value>(json)
+            .map_err(|e| e.to_string())
+            .and_then(|value| RewriteOptions::from_value(value, config).map_err(|e| e.to_string()))
+    }
+
+    fn is_parse_error(result: Result<RewrittenOutput, RewriteError>) -> bool {
+        matches!(
+            result,
+            Err(RewriteError {
+                code: RewriteErrorCode::ParseError,
+                ..
+            })
+        )
+    }
+
+    #[test]
+    fn test_parser_options_defaults() -> Result<(), String> {
+        let config = RewriterConfig::from_value(Value::Null)
+            .map_err(|e| e.to_string())?
+            .to_config();
+
+        assert_that(&config.parser_options).is_equal_to(ParserOptions {
+            decorators: false,
+            decorators_before_export: false,
+            is_module: ModuleKind::Auto,
+            target: EsVersion::EsNext,
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_config() -> Result<(), String> {
+        let value = serde_json::from_str::<Value>(
+            r#"{"parserOptions": {"decorators": true, "decoratorsBeforeExport": true, "isModule": "commonjs", "target": "es2020"}}"#,
+        )
+        .map_err(|e| e.to_string())?;
+        let config = RewriterConfig::from_value(value)
+            .map_err(|e| e.to_string())?
+            .to_config();
+
+        assert_that(&config.parser_options).is_equal_to(ParserOptions {
+            decorators: true,
+            decorators_before_export: true,
+            is_module: ModuleKind::CommonJs,
+            target: EsVersion::Es2020,
+        });
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_config_errors() -> Result<(), String> {
+        let value = serde_json::from_str::<Value>(
+            r#"{"parserOptions": {"decorators": "yes", "isModule": "esm", "target": "es2030", "jsx": true}}"#,
+        )
+        .map_err(|e| e.to_string())?;
+        let errors = match RewriterConfig::from_value(value) {
+            Ok(_) => return Err("Config expected to be invalid".to_string()),
+            Err(errors) => errors.errors,
+        };
+
+        assert_that(&errors).is_equal_to(vec![
+            ConfigError {
+                key: "parserOptions.decorators".to_string(),
+                message: "expected a boolean".to_string(),
+            },
+            ConfigError {
+                key: "parserOptions.isModule".to_string(),
+                message:
+                    "unknown module kind 'esm', expected one of auto, module, script, commonjs"
+                        .to_string(),
+            },
+            ConfigError {
+                key: "parserOptions.jsx".to_string(),
+                message: "unknown key".to_string(),
+            },
+            ConfigError {
+                key: "parserOptions.target".to_string(),
+                message: "unknown target 'es2030'".to_string(),
+            },
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_legacy_decorators() -> Result<(), String> {
+        let code = "@Injectable()
+class Service {
+    concat(a, b) {
+        return a + b;
+    }
+}";
+        let mut config = get_default_config(false);
+        assert_that(&is_parse_error(rewrite_js_with_config(
+            code.to_string(),
+            &config,
+        )))
+        .is_true();
+
+        config.parser_options.decorators = true;
+        let rewritten =
+            rewrite_js_with_config(code.to_string(), &config).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("return _ddiast.plusOperator(a + b, a, b);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_typescript_decorators() -> Result<(), String> {
+        let code = "@Injectable()
+class Service {
+    concat(a: string, b: string) {
+        return a + b;
+    }
+}";
+        let mut config = get_default_config(false);
+        config.parser_options.decorators = false;
+        let rewritten = crate::rewriter::rewrite_js(
+            code.to_string(),
+            "test.ts",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("return _ddiast.plusOperator(a + b, a, b);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_decorators_before_export() -> Result<(), String> {
+        let code = "@Controller()
+export class Api {
+    path(a) {
+        return '/' + a;
+    }
+}";
+        let mut config = get_default_config(false);
+        config.parser_options.decorators = true;
+        config.parser_options.decorators_before_export = true;
+        let rewritten = crate::rewriter::rewrite_js(
+            code.to_string(),
+            "test.mjs",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("return _ddiast.plusOperator('/' + a, '/', a);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_module_is_strict() -> Result<(), String> {
+        let code = "with (obj) { a + b; }";
+        let mut config = get_default_config(false);
+        crate::rewriter::rewrite_js(code.to_string(), "test.mjs", &config, &DefaultFileReader {})
+            .map_err(|e| e.to_string())?;
+
+        config.parser_options.is_module = ModuleKind::Module;
+        assert_that(&is_parse_error(crate::rewriter::rewrite_js(
+            code.to_string(),
+            "test.mjs",
+            &config,
+            &DefaultFileReader {},
+        )))
+        .is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_script_rejects_imports() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.parser_options.is_module = ModuleKind::Script;
+        assert_that(&is_parse_error(crate::rewriter::rewrite_js(
+            "import a from 'a'; const b = a + 'b';".to_string(),
+            "test.cjs",
+            &config,
+            &DefaultFileReader {},
+        )))
+        .is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_parser_options_commonjs_top_level_return() -> Result<(), String> {
+        let code = "if (!a) return;\nmodule.exports = a + b;";
+        let mut config = get_default_config(false);
+        config.parser_options.is_module = ModuleKind::Script;
+        assert_that(&is_parse_error(crate::rewriter::rewrite_js(
+            code.to_string(),
+            "test.cjs",
+            &config,
+            &DefaultFileReader {},
+        )))
+        .is_true();
+
+        config.parser_options.is_module = ModuleKind::CommonJs;
+        let rewritten = crate::rewriter::rewrite_js(
+            code.to_string(),
+            "test.cjs",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("module.exports = _ddiast.plusOperator(a + b, a, b);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_override_parser_options() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.parser_options.decorators_before_export = true;
+
+        let options = parse_options(
+            r#"{"parserOptions": {"decorators": true, "isModule": "module"}}"#,
+            &config,
+        )?;
+        let file_config = options.apply(&config);
+
+        assert_that(&file_config.parser_options).is_equal_to(ParserOptions {
+            decorators: true,
+            decorators_before_export: true,
+            is_module: ModuleKind::Module,
+            target: EsVersion::EsNext,
+        });
+        assert_that(&config.parser_options.decorators).is_false();
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_empty_keeps_config() -> Result<(), String> {
+        let config = get_default_config(false);
+
+        let file_config = parse_options("null", &config)?.apply(&config);
+
+        assert_that(&std::ptr::eq(&*file_config, &config)).is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_errors() -> Result<(), String> {
+        let config = get_default_config(false);
+        let error = match parse_options(
+            r#"{"parserOptions": {"isModule": 1}, "jsx": true}"#,
+            &config,
+        ) {
+            Ok(_) => return Err("Options expected to be invalid".to_string()),
+            Err(error) => error,
+        };
+
+        assert_that(&error).is_equal_to(
+            "Invalid rewrite options: jsx: unknown key, parserOptions.isModule: expected a string"
+                .to_string(),
+        );
+        Ok(())
+    }
+}
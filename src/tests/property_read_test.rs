@@ -0,0 +1,139 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{
+            assert_not_modified, csi_op_from_str, csi_property_read_from_str,
+            rewrite_js_with_csi_methods,
+        },
+        visitor::csi_methods::CsiMethods,
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_property_read() -> Result<(), String> {
+        let original_code = "const a = req.query.a;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_property_read_from_str("queryRead", "req.query")];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = req.query, _ddiast.queryRead(__datadog_test_0.a, __datadog_test_0, \"a\"));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_read_computed_key() -> Result<(), String> {
+        let original_code = "const a = req.headers[h];
+const b = req.headers['x-forwarded-for'];
+const c = req['query'].c.d;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_property_read_from_str("queryRead", "req.query"),
+            csi_property_read_from_str("headersRead", "req.headers"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = req.headers, __datadog_test_1 = h, _ddiast.headersRead(__datadog_test_0[__datadog_test_1], __datadog_test_0, __datadog_test_1));");
+        assert_that(&rewritten.code).contains("const b = (__datadog_test_0 = req.headers, _ddiast.headersRead(__datadog_test_0['x-forwarded-for'], __datadog_test_0, 'x-forwarded-for'));");
+        assert_that(&rewritten.code).contains("const c = (__datadog_test_0 = req['query'], _ddiast.queryRead(__datadog_test_0.c, __datadog_test_0, \"c\")).d;");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_read_this_receiver() -> Result<(), String> {
+        let original_code = "class A {
+    m() {
+        return this.body.a;
+    }
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_property_read_from_str("bodyRead", "this.body")];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("return (__datadog_test_0 = this.body, _ddiast.bodyRead(__datadog_test_0.a, __datadog_test_0, \"a\"));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_read_in_operation() -> Result<(), String> {
+        let original_code = "const a = req.query.a + req.headers[h + 'x'];".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_property_read_from_str("queryRead", "req.query"),
+            csi_property_read_from_str("headersRead", "req.headers"),
+            csi_op_from_str("plusOperator", None),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("__datadog_test_0 = req.query, _ddiast.queryRead(__datadog_test_0.a, __datadog_test_0, \"a\")");
+        assert_that(&rewritten.code).contains("__datadog_test_2 = _ddiast.plusOperator(h + 'x', h, 'x'), _ddiast.headersRead(__datadog_test_1[__datadog_test_2], __datadog_test_1, __datadog_test_2)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_read_not_modified() -> Result<(), String> {
+        let original_code = "req.query.a = 1;
+req.query.b++;
+delete req.query.c;
+({ a: req.query.d } = o);
+[req.query.e] = arr;
+(req.query.f) = 2;
+for (req.query.g in o) {}
+req.query.get(a);
+(req.query.get)(a);
+req.query.tag`x`;
+const { q } = req.query;
+const p = req.params.a;
+const r = other.query.a;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_property_read_from_str("queryRead", "req.query")];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_read_of_written_member() -> Result<(), String> {
+        let original_code = "req.query.a.b = 1;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_property_read_from_str("queryRead", "req.query")];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = req.query, _ddiast.queryRead(__datadog_test_0.a, __datadog_test_0, \"a\")).b = 1;");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_read_typescript_target() -> Result<(), String> {
+        let original_code = "(req.query.a as any) = 1;
+req.query.b!++;"
+            .to_string();
+        let js_file = "test.ts".to_string();
+        let methods = vec![csi_property_read_from_str("queryRead", "req.query")];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        telemetry::Telemetry,
+        tests::{
+            assert_not_modified, csi_op_from_str, get_default_config, rewrite_js_with_config,
+            rewrite_js_with_csi_methods,
+        },
+        visitor::csi_methods::CsiMethods,
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_property_write() -> Result<(), String> {
+        let original_code = "obj[key] = value;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = obj, __datadog_test_1 = key, __datadog_test_2 = value, _ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = __datadog_test_2, __datadog_test_0, __datadog_test_1, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_nested_member() -> Result<(), String> {
+        let original_code = "obj[a][b] = 'polluted';".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = obj[a], __datadog_test_1 = b, _ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = 'polluted', __datadog_test_0, __datadog_test_1, 'polluted'));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_this() -> Result<(), String> {
+        let original_code = "class A {
+    set(key, value) {
+        this[key] = value;
+    }
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = key, __datadog_test_1 = value, _ddiast.propertyWriteOperator(this[__datadog_test_0] = __datadog_test_1, this, __datadog_test_0, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_anonymous_function() -> Result<(), String> {
+        let original_code = "obj[a] = function () {};
+obj[b] = () => {};
+obj[c] = class {};
+obj[d] = function named() {};"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code)
+            .contains("__datadog_test_2 = (0, function() {}), _ddiast.propertyWriteOperator(");
+        assert_that(&rewritten.code)
+            .contains("__datadog_test_2 = (0, ()=>{}), _ddiast.propertyWriteOperator(");
+        assert_that(&rewritten.code)
+            .contains("__datadog_test_2 = (0, class {\n}), _ddiast.propertyWriteOperator(");
+        assert_that(&rewritten.code)
+            .contains("__datadog_test_2 = function named() {}, _ddiast.propertyWriteOperator(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_chained() -> Result<(), String> {
+        let original_code = "const r = obj[a] = other[b] = v;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const r = (__datadog_test_3 = obj, __datadog_test_4 = a, __datadog_test_5 = (__datadog_test_0 = other, __datadog_test_1 = b, __datadog_test_2 = v, _ddiast.propertyWriteOperator(");
+        assert_that(&rewritten.code).contains("_ddiast.propertyWriteOperator(__datadog_test_3[__datadog_test_4] = __datadog_test_5, __datadog_test_3, __datadog_test_4, __datadog_test_5));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_logical_assignment() -> Result<(), String> {
+        let original_code = "obj[key()] ??= value;
+obj[a] ||= 'default';
+obj[b] &&= value;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = obj, __datadog_test_1 = key(), __datadog_test_0[__datadog_test_1] ?? (__datadog_test_2 = value, _ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = __datadog_test_2, __datadog_test_0, __datadog_test_1, __datadog_test_2)));");
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = obj, __datadog_test_1 = a, __datadog_test_0[__datadog_test_1] || _ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = 'default', __datadog_test_0, __datadog_test_1, 'default'));");
+        assert_that(&rewritten.code).contains("__datadog_test_0[__datadog_test_1] && (__datadog_test_2 = value, _ddiast.propertyWriteOperator(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_logical_assignment_anonymous_function() -> Result<(), String> {
+        let original_code = "obj[key] ??= function () {};".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("__datadog_test_0[__datadog_test_1] ?? (__datadog_test_2 = (0, function() {}), _ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = __datadog_test_2, __datadog_test_0, __datadog_test_1, __datadog_test_2)));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_logical_assignment_identifier() -> Result<(), String> {
+        let original_code = "x ??= tainted;
+y ||= tainted;
+z &&= tainted;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_op_from_str("propertyWriteOperator", None),
+            csi_op_from_str("plusOperator", None),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_not_modified() -> Result<(), String> {
+        let original_code = "obj['a'] = v;
+obj[0] = v;
+obj.a = v;
+obj[a] += v;
+obj[a] -= v;
+obj.a ??= v;
+[obj[a]] = v;
+({ b: obj[a] } = v);
+a = v;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("propertyWriteOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_disabled() -> Result<(), String> {
+        let rewritten = rewrite_js_with_csi_methods(
+            "obj[key] = value + a;".to_string(),
+            "test.js".to_string(),
+            &CsiMethods::new(&[csi_op_from_str("plusOperator", None)]),
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code)
+            .contains("obj[key] = _ddiast.plusOperator(value + a, value, a);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_property_write_telemetry_tag() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.csi_methods = CsiMethods::new(&[csi_op_from_str("propertyWriteOperator", None)]);
+        let rewritten = rewrite_js_with_config("obj[a] = v; obj[b] = w;".to_string(), &config)
+            .map_err(|e| e.to_string())?;
+
+        let transform_status = rewritten.transform_status.ok_or("missing status")?;
+        let propagation_debug = transform_status
+            .telemetry
+            .get_propagation_debug()
+            .ok_or("missing debug telemetry")?;
+        assert_that(&propagation_debug.get("[]=")).is_equal_to(Some(&2));
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::tests::{assert_not_modified, rewrite_js};
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_destructured_prototype_method_call() -> Result<(), String> {
+        let original_code = "const { concat } = String.prototype;
+function f(a, b) {
+    return concat.call(a, b);
+}"
+        .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("return (__datadog_test_0 = a, __datadog_test_1 = concat, __datadog_test_2 = b, _ddiast.stringConcat(__datadog_test_1.call(__datadog_test_0, __datadog_test_2), __datadog_test_1, __datadog_test_0, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_stored_prototype_method_apply() -> Result<(), String> {
+        let original_code = "const sub = String.prototype.substring;
+const alias = sub;
+const a = alias.apply(b, [1, c]);"
+            .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const a = (__datadog_test_0 = b, __datadog_test_1 = alias, __datadog_test_2 = c, _ddiast.stringSubstring(__datadog_test_1.apply(__datadog_test_0, [");
+        Ok(())
+    }
+
+    #[test]
+    fn test_stored_prototype_method_spread_call() -> Result<(), String> {
+        let original_code = "const { concat } = String.prototype;
+const a = concat.call(...b);"
+            .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.stringConcat(__datadog_test_0.call(...__datadog_test_1), __datadog_test_0, ...__datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_stored_prototype_method_shadowed() -> Result<(), String> {
+        let original_code = "const concat2 = String.prototype.concat;
+const alias = concat2;
+function h(concat2) {
+    return concat2.call(a, b);
+}
+function g() {
+    const alias = other;
+    return alias.call(a, b);
+}"
+        .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_bound_prototype_method() -> Result<(), String> {
+        let original_code = "const s = getString();
+const sub = String.prototype.substring.bind(s);
+const alias = sub;
+function f(b) {
+    return sub(1, b) + alias(2);
+}"
+        .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = b, _ddiast.stringSubstring(sub(1, __datadog_test_0), String.prototype.substring, s, 1, __datadog_test_0))");
+        assert_that(&rewritten.code)
+            .contains("_ddiast.stringSubstring(alias(2), String.prototype.substring, s, 2)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bound_prototype_method_with_unstable_receiver() -> Result<(), String> {
+        let original_code = "let t = getString();
+const bySub = String.prototype.substring.bind(t);
+const s = getString();
+var byVar = String.prototype.substring.bind(s);
+const shadowed = String.prototype.substring.bind(s);
+const withArgs = String.prototype.substring.bind(s, 1);
+function f(s) {
+    return bySub(1) + byVar(1) + shadowed(1) + withArgs(2);
+}"
+        .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.plusOperator(");
+        assert_that(&rewritten.code).does_not_contain("_ddiast.stringSubstring(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_bound_prototype_method_with_declared_class() -> Result<(), String> {
+        let original_code = "const s = getString();
+const sub = String.prototype.substring.bind(s);
+class String {}
+sub(1);"
+            .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_prototype_method_reference() -> Result<(), String> {
+        let original_code = "const { concat } = other;
+const trim = String.trim;
+concat.call(a, b);
+trim.call(a);
+unknown.call(a, b);"
+            .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.js".to_string()).map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+}
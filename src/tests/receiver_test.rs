@@ -0,0 +1,182 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{
+            assert_not_modified, csi_from_str, csi_receiver_from_str, rewrite_js_with_csi_methods,
+        },
+        visitor::csi_methods::CsiMethods,
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_receiver_require_call() -> Result<(), String> {
+        let original_code = "require('child_process').exec(cmd);".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = require('child_process'), __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = cmd, _ddiast.childProcessExec(__datadog_test_1.call(__datadog_test_0, __datadog_test_2), __datadog_test_1, __datadog_test_0, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_required_namespace() -> Result<(), String> {
+        let original_code = "const cp = require('node:child_process');
+function run(cmd) {
+    other.exec(cmd);
+    return cp.exec(cmd);
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("other.exec(cmd);");
+        assert_that(&rewritten.code).contains("return (__datadog_test_0 = cp, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = cmd, _ddiast.childProcessExec(__datadog_test_1.call(__datadog_test_0, __datadog_test_2), __datadog_test_1, __datadog_test_0, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_destructured_require() -> Result<(), String> {
+        let original_code = "const { exec, join: pathJoin } = require('child_process');
+exec(cmd);
+pathJoin(a, b);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "(__datadog_test_0 = cmd, _ddiast.childProcessExec(exec(__datadog_test_0), exec, undefined, __datadog_test_0));",
+        );
+        assert_that(&rewritten.code).contains("pathJoin(a, b);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_required_export() -> Result<(), String> {
+        let original_code = "const run = require('child_process').exec;
+run(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "_ddiast.childProcessExec(run(__datadog_test_0), run, undefined, __datadog_test_0));",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_imports() -> Result<(), String> {
+        let original_code = "import path from 'path';
+import * as cp from 'node:child_process';
+import { exec as run } from 'child_process';
+export const p = path.join(a, b);
+cp.exec(cmd);
+run(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.pathJoin(");
+        assert_that(&rewritten.code).contains("__datadog_test_0 = cp, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = cmd, _ddiast.childProcessExec(");
+        assert_that(&rewritten.code).contains(
+            "_ddiast.childProcessExec(run(__datadog_test_0), run, undefined, __datadog_test_0));",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_transpiled_indirect_call() -> Result<(), String> {
+        let original_code = "var _child_process = require(\"child_process\");
+(0, _child_process.exec)(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("(__datadog_test_0 = _child_process.exec, __datadog_test_1 = cmd, _ddiast.childProcessExec(__datadog_test_0(__datadog_test_1), __datadog_test_0, undefined, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_not_matching() -> Result<(), String> {
+        let original_code = "const path = require('path');
+const cp = require('other');
+const exec = require('other').exec;
+const a = arr.join(',');
+cp.exec(cmd);
+exec(cmd);
+path.exec(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("join", "pathJoin", "path"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_receiver_qualified_and_unqualified() -> Result<(), String> {
+        let csi_methods = CsiMethods::new(&[
+            csi_receiver_from_str("join", "pathJoin", "path"),
+            csi_from_str("join", Some("arrayJoin")),
+        ]);
+        let rewritten = rewrite_js_with_csi_methods(
+            "const path = require('path');
+const a = path.join(b, c);
+const d = arr.join(',');"
+                .to_string(),
+            "test.js".to_string(),
+            &csi_methods,
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.pathJoin(__datadog_test_1.call(");
+        assert_that(&rewritten.code).contains("_ddiast.arrayJoin(__datadog_test_1.call(");
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewrite_error::{RewriteError, RewriteErrorCode},
+        tests::rewrite_js,
+    };
+    use serde_json::json;
+    use speculoos::{assert_that, option::OptionAssertions, string::StrAssertions};
+
+    fn rewrite_js_error(code: &str) -> Result<RewriteError, String> {
+        match rewrite_js(code.to_string(), "test.js".to_string()) {
+            Ok(_) => Err("Rewrite expected to fail".to_string()),
+            Err(error) => Ok(error),
+        }
+    }
+
+    #[test]
+    fn test_parse_error() -> Result<(), String> {
+        let error = rewrite_js_error("const a = 1;\nconst b = a +;")?;
+
+        assert_that(&error.code).is_equal_to(RewriteErrorCode::ParseError);
+        assert_that(&error.file).is_equal_to("test.js".to_string());
+        assert_that(&error.line).is_some().is_equal_to(2);
+        assert_that(&error.column).is_some().is_equal_to(13);
+        assert_that(&error.message).is_equal_to("Expression expected".to_string());
+        assert_that(&error.to_string())
+            .is_equal_to("Error parsing test.js:2:13: Expression expected".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_recovered_error() -> Result<(), String> {
+        let error = rewrite_js_error("'use strict';\nvar a = 010 + b;")?;
+
+        assert_that(&error.code).is_equal_to(RewriteErrorCode::ParseError);
+        assert_that(&error.line).is_some().is_equal_to(2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_panic_error() {
+        let payload = std::panic::catch_unwind(|| panic!("boom {}", 1)).unwrap_err();
+        let error = RewriteError::panic("test.js", payload);
+
+        assert_that(&error.code).is_equal_to(RewriteErrorCode::Panic);
+        assert_that(&error.message).is_equal_to("boom 1".to_string());
+        assert_that(&error.to_string()).starts_with("Panic rewriting test.js");
+    }
+
+    #[test]
+    fn test_serialized_error() -> Result<(), String> {
+        let parse_error = rewrite_js_error("const a = ;")?;
+
+        assert_that(&serde_json::to_value(&parse_error).map_err(|e| e.to_string())?).is_equal_to(
+            json!({
+                "code": "ParseError",
+                "file": "test.js",
+                "line": 1,
+                "column": 10,
+                "diagnostic": "Expression expected"
+            }),
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        config::{ConfigError, RewriteOptions},
+        rewriter::Config,
+        telemetry::{Telemetry, TelemetryVerbosity},
+        tests::{get_default_config, rewrite_js_with_config},
+    };
+    use serde_json::Value;
+    use speculoos::{assert_that, prelude::BooleanAssertions, string::StrAssertions};
+
+    fn parse_options(json: &str, config: &Config) -> Result<RewriteOptions, Vec<ConfigError>> {
+        let value = serde_json::from_str::<Value>(json).expect("invalid test json");
+        RewriteOptions::from_value(value, config).map_err(|errors| errors.errors)
+    }
+
+    #[test]
+    fn test_rewrite_options_csi_methods_subset() -> Result<(), String> {
+        let config = get_default_config(false);
+        let code = "{const a = b + c.substring(1);}";
+
+        let options = parse_options(r#"{"csiMethods": ["substring"]}"#, &config)
+            .map_err(|errors| format!("{errors:?}"))?;
+        let rewritten = rewrite_js_with_config(code.to_string(), &options.apply(&config))
+            .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.stringSubstring(");
+        assert_that(&rewritten.code).does_not_contain("_ddiast.plusOperator(");
+
+        let options = parse_options("{}", &config).map_err(|errors| format!("{errors:?}"))?;
+        let rewritten = rewrite_js_with_config(code.to_string(), &options.apply(&config))
+            .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.stringSubstring(");
+        assert_that(&rewritten.code).contains("_ddiast.plusOperator(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_empty_csi_methods_subset() -> Result<(), String> {
+        let config = get_default_config(false);
+
+        let options = parse_options(r#"{"csiMethods": []}"#, &config)
+            .map_err(|errors| format!("{errors:?}"))?;
+        let rewritten = rewrite_js_with_config(
+            "{const a = b + c.substring(1);}".to_string(),
+            &options.apply(&config),
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).does_not_contain("_ddiast.");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_literals() -> Result<(), String> {
+        let config = get_default_config(false);
+        let code = "{const a = 'literal' + b;}";
+
+        let options = parse_options("{}", &config).map_err(|errors| format!("{errors:?}"))?;
+        let rewritten = rewrite_js_with_config(code.to_string(), &options.apply(&config))
+            .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.literals_result.is_none()).is_true();
+
+        let options = parse_options(r#"{"literals": true}"#, &config)
+            .map_err(|errors| format!("{errors:?}"))?;
+        let rewritten = rewrite_js_with_config(code.to_string(), &options.apply(&config))
+            .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.literals_result.is_some()).is_true();
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_telemetry_verbosity() -> Result<(), String> {
+        let config = get_default_config(false);
+
+        let options = parse_options(r#"{"telemetryVerbosity": "OFF"}"#, &config)
+            .map_err(|errors| format!("{errors:?}"))?;
+        let rewritten = rewrite_js_with_config(
+            "{const a = b.substring(1);}".to_string(),
+            &options.apply(&config),
+        )
+        .map_err(|e| e.to_string())?;
+
+        let status = rewritten
+            .transform_status
+            .ok_or("missing transform status")?;
+        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(0);
+        assert_that(&config.verbosity).is_equal_to(TelemetryVerbosity::Debug);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_override_config() -> Result<(), String> {
+        let config = get_default_config(false);
+
+        let options = parse_options(
+            r#"{"chainSourceMap": true, "literals": true, "telemetryVerbosity": "mandatory"}"#,
+            &config,
+        )
+        .map_err(|errors| format!("{errors:?}"))?;
+        let file_config = options.apply(&config);
+
+        assert_that(&file_config.chain_source_map).is_true();
+        assert_that(&file_config.literals).is_true();
+        assert_that(&file_config.verbosity).is_equal_to(TelemetryVerbosity::Mandatory);
+        assert_that(&file_config.local_var_prefix).is_equal_to(&config.local_var_prefix);
+        assert_that(&config.chain_source_map).is_false();
+        assert_that(&config.literals).is_false();
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_options_errors() -> Result<(), String> {
+        let config = get_default_config(false);
+
+        let errors = match parse_options(
+            r#"{"csiMethods": ["substring", "eval", 1], "literals": "no", "telemetryVerbosity": "ALL"}"#,
+            &config,
+        ) {
+            Ok(_) => return Err("Options expected to be invalid".to_string()),
+            Err(errors) => errors,
+        };
+
+        assert_that(&errors).is_equal_to(vec![
+            ConfigError {
+                key: "csiMethods[1]".to_string(),
+                message: "unknown csi method 'eval'".to_string(),
+            },
+            ConfigError {
+                key: "csiMethods[2]".to_string(),
+                message: "expected a string".to_string(),
+            },
+            ConfigError {
+                key: "literals".to_string(),
+                message: "expected a boolean".to_string(),
+            },
+            ConfigError {
+                key: "telemetryVerbosity".to_string(),
+                message: "unknown telemetry verbosity 'ALL'".to_string(),
+            },
+        ]);
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewriter::generate_prefix_stmts,
+        tests::{csi_op_from_str, get_default_config, rewrite_js_with_config},
+        util::DefaultFileReader,
+        visitor::{
+            csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
+            site_visitor::SiteInfo,
+        },
+    };
+    use speculoos::{assert_that, option::OptionAssertions, string::StrAssertions};
+
+    fn site(
+        id: usize,
+        kind: &str,
+        tag: &str,
+        line: usize,
+        column: usize,
+        function: Option<&str>,
+    ) -> SiteInfo {
+        SiteInfo {
+            id,
+            kind: kind.to_string(),
+            tag: tag.to_string(),
+            line: Some(line),
+            column: Some(column),
+            function: function.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_site_ids() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.site_ids = true;
+        let rewritten = rewrite_js_with_config(
+            "function f(a, b) {
+    let c = a + b;
+    c += `${a}${b}`;
+    return c.substring(1);
+}"
+            .to_string(),
+            &config,
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.plusOperator(0, a + b, a, b)");
+        assert_that(&rewritten.code)
+            .contains("_ddiast.tplOperator(1, `${__datadog_test_0}${__datadog_test_1}`, __datadog_test_0, __datadog_test_1)");
+        assert_that(&rewritten.code)
+            .contains("_ddiast.plusOperator(2, __datadog_test_2 + __datadog_test_3");
+        assert_that(&rewritten.code)
+            .contains("_ddiast.stringSubstring(3, __datadog_test_1.call(__datadog_test_0, 1)");
+        assert_that(&rewritten.sites).is_some().is_equal_to(vec![
+            site(0, "operator", "+", 2, 13, Some("f")),
+            site(1, "template", "Tpl", 3, 10, Some("f")),
+            site(2, "operator", "+=", 3, 5, Some("f")),
+            site(3, "method", "substring", 4, 12, Some("f")),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_site_kinds() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.site_ids = true;
+        config.csi_methods = CsiMethods::new(&[
+            csi_op_from_str("propertyWriteOperator", None),
+            csi_op_from_str("taggedTplOperator", None),
+            CsiMethod::new(
+                "URL".to_string(),
+                Some("urlConstructor".to_string()),
+                false,
+                false,
+                CsiMethodKind::Constructor,
+                None,
+            ),
+            CsiMethod::new(
+                "queryRead".to_string(),
+                None,
+                false,
+                false,
+                CsiMethodKind::PropertyRead,
+                None,
+            )
+            .with_receiver_pattern(Some("req.query".to_string())),
+        ]);
+        let rewritten = crate::rewriter::rewrite_js(
+            "const u = new URL(p);\nconst q = req.query.a;\nobj[k] = v;\nconst t = tag`a${b}`;"
+                .to_string(),
+            "test.js",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+
+        let kinds = rewritten
+            .sites
+            .ok_or("missing sites")?
+            .into_iter()
+            .map(|site| site.kind)
+            .collect::<Vec<_>>();
+        assert_that(&kinds).is_equal_to(
+            [
+                "constructor",
+                "propertyRead",
+                "propertyWrite",
+                "taggedTemplate",
+            ]
+            .map(String::from)
+            .to_vec(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_site_ids_disabled() -> Result<(), String> {
+        let source_map_reader = DefaultFileReader {};
+        let rewritten = crate::rewriter::rewrite_js(
+            "const a = b + c;".to_string(),
+            "test.js",
+            &get_default_config(false),
+            &source_map_reader,
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.plusOperator(b + c, b, c);");
+        assert_that(&rewritten.sites).is_none();
+        Ok(())
+    }
+
+    #[test]
+    fn test_site_ids_are_deterministic() -> Result<(), String> {
+        let code = "const a = b + c; const d = e.trim() + f;";
+
+        let mut config = get_default_config(false);
+        config.site_ids = true;
+
+        let first = rewrite_js_with_config(code.to_string(), &config).map_err(|e| e.to_string())?;
+        let second =
+            rewrite_js_with_config(code.to_string(), &config).map_err(|e| e.to_string())?;
+
+        assert_that(&first.code).is_equal_to(&second.code);
+        assert_that(&first.sites).is_equal_to(&second.sites);
+        Ok(())
+    }
+
+    #[test]
+    fn test_site_function_names() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.site_ids = true;
+        let rewritten = rewrite_js_with_config(
+            "const a = b + c;
+const arrow = (x) => x + 1;
+obj.method = function (x) { return x + 2; };
+class Service {
+    run(x) { return x + 3; }
+    handler = (x) => x + 4;
+}
+const o = { build(x) { return x + 5; }, fn: function named(x) { return x + 6; } };
+setTimeout(function () { a + 7; });
+function withDefault(x, y = x + b()) { return y; }"
+                .to_string(),
+            &config,
+        )
+        .map_err(|e| e.to_string())?;
+
+        let functions = rewritten
+            .sites
+            .ok_or("missing sites")?
+            .into_iter()
+            .map(|site| site.function)
+            .collect::<Vec<_>>();
+        assert_that(&functions).is_equal_to(vec![
+            None,
+            Some("arrow".to_string()),
+            Some("method".to_string()),
+            Some("Service.run".to_string()),
+            Some("Service.handler".to_string()),
+            Some("build".to_string()),
+            Some("named".to_string()),
+            Some("<anonymous>".to_string()),
+            Some("withDefault".to_string()),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_site_ids_with_locations() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.site_ids = true;
+        config.call_site_locations = true;
+        let rewritten = crate::rewriter::rewrite_js(
+            "const a = b + c;".to_string(),
+            "test.js",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.plusOperator(\"0:1:11\", 0, b + c, b, c);");
+        assert_that(&rewritten.sites)
+            .is_some()
+            .is_equal_to(vec![site(0, "operator", "+", 1, 11, None)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_site_ids_prefix_noops() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.site_ids = true;
+        config.call_site_locations = true;
+        config.csi_methods = CsiMethods::new(&[
+            csi_op_from_str("plusOperator", None),
+            CsiMethod::new(
+                "exec".to_string(),
+                None,
+                false,
+                false,
+                CsiMethodKind::Before,
+                None,
+            ),
+        ]);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, true, true);
+        let rewritten = crate::rewriter::rewrite_js(
+            "const a = b + c;\nconst d = e.exec(a);".to_string(),
+            "test.js",
+            &config,
+            &DefaultFileReader {},
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains(
+            "const noop = (location, siteId, res)=>res, noopBefore = (location, siteId, fn, self, args)=>args;",
+        );
+        Ok(())
+    }
+}
@@ -79,7 +79,7 @@ mod tests {
                 return Some(decode_data_url(url).unwrap());
             }
         }
-        return None;
+        None
     }
 
     fn get_rewritten_js(file_js: &str) -> Result<RewrittenOutput, String> {
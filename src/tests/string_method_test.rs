@@ -27,6 +27,41 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_computed_substring() -> Result<(), String> {
+        let original_code = "{const a = b['substring'](1); const c = d[`trim`]();}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.substring, _ddiast.stringSubstring(__datadog_test_1.call(__datadog_test_0, 1), __datadog_test_1, __datadog_test_0, 1));");
+        assert_that(&rewritten.code)
+            .contains("const c = (__datadog_test_0 = d, __datadog_test_1 = __datadog_test_0.trim, _ddiast.stringTrim(__datadog_test_1.call(__datadog_test_0), __datadog_test_1, __datadog_test_0));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_computed_literal_concat() -> Result<(), String> {
+        let original_code = "{const a = 'hello'['concat'](b);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = 'hello'.concat, __datadog_test_1 = b, _ddiast.stringConcat(__datadog_test_0.call('hello', __datadog_test_1), __datadog_test_0, 'hello', __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_computed_not_static_key() -> Result<(), String> {
+        let original_code =
+            "{const a = b[method](1); const c = d[`${e}`](); const f = g['trim-end']();}"
+                .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("const a = b[method](1);");
+        assert_that(&rewritten.code).does_not_contain("_ddiast.stringTrim(");
+        assert_that(&rewritten.code).contains("const f = g['trim-end']();");
+        Ok(())
+    }
+
     #[test]
     fn test_call_substring() -> Result<(), String> {
         let original_code = "{const a = b().substring(1);}".to_string();
@@ -207,9 +242,9 @@ mod tests {
     fn test_chained_calls_with_exclusions() -> Result<(), String> {
         let original_code = "{const a = b.concat('a').substring(2).trim();}".to_string();
         let js_file = "test.js".to_string();
-        let mut methods = vec![csi_from_str("concat", Some("stringConcat"))];
+        let methods = vec![csi_from_str("concat", Some("stringConcat"))];
         let rewritten =
-            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&mut methods))
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
                 .map_err(|e| e.to_string())?;
         assert_that(&rewritten.code).contains("let __datadog_test_0, __datadog_test_1;
     const a = (__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.concat, _ddiast.stringConcat(__datadog_test_1.call(__datadog_test_0, 'a'), __datadog_test_1, __datadog_test_0, 'a')).substring(2).trim();");
@@ -239,9 +274,9 @@ mod tests {
     fn test_plus_operator_csi_method_but_plus_exclusion() -> Result<(), String> {
         let original_code = "{const a = b.plusOperator(c + d)}".to_string();
         let js_file = "test.js".to_string();
-        let mut methods = vec![csi_from_str("plusOperator", Some("plusOperator"))];
+        let methods = vec![csi_from_str("plusOperator", Some("plusOperator"))];
         let rewritten =
-            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&mut methods))
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
                 .map_err(|e| e.to_string())?;
         assert_that(&rewritten.code).contains("let __datadog_test_0, __datadog_test_1;
     const a = (__datadog_test_0 = b, __datadog_test_1 = __datadog_test_0.plusOperator, _ddiast.plusOperator(__datadog_test_1.call(__datadog_test_0, c + d), __datadog_test_1, __datadog_test_0));");
@@ -252,9 +287,9 @@ mod tests {
     fn test_array_and_join() -> Result<(), String> {
         let original_code = "{[str, str].join();}".to_string();
         let js_file = "test.js".to_string();
-        let mut methods = vec![csi_from_str("join", None)];
+        let methods = vec![csi_from_str("join", None)];
         let rewritten =
-            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&mut methods))
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
                 .map_err(|e| e.to_string())?;
         assert_that(&rewritten.code).contains("let __datadog_test_0, __datadog_test_1;
     (__datadog_test_0 = [
@@ -340,4 +375,55 @@ mod tests {
     }");
         Ok(())
     }
+
+    #[test]
+    fn test_indirect_call_substring() -> Result<(), String> {
+        let original_code = "{const a = (0, _lib.substring)(1, b);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = _lib.substring, __datadog_test_1 = b, _ddiast.stringSubstring(__datadog_test_0(1, __datadog_test_1), __datadog_test_0, undefined, 1, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_indirect_call_spread() -> Result<(), String> {
+        let original_code = "{(0, a.trim)(...b);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("_ddiast.stringTrim(__datadog_test_0(...__datadog_test_1), __datadog_test_0, undefined, ...__datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_reflect_apply_prototype_concat() -> Result<(), String> {
+        let original_code =
+            "{const a = Reflect.apply(String.prototype.concat, b, [c]);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = b, __datadog_test_1 = String.prototype.concat, __datadog_test_2 = c, _ddiast.stringConcat(__datadog_test_1.apply(__datadog_test_0, [
+        __datadog_test_2
+    ]), __datadog_test_1, __datadog_test_0, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_reflect_apply_member() -> Result<(), String> {
+        let original_code = "{const a = Reflect.apply(b.trim, c, []);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = c, __datadog_test_1 = b.trim, _ddiast.stringTrim(__datadog_test_1.apply(__datadog_test_0, []), __datadog_test_1, __datadog_test_0));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_indirect_call_and_reflect_apply_not_csi_method() -> Result<(), String> {
+        let original_code = "{const a = (0, b.other)(c); const d = Reflect.apply(fn, e, f); const g = Reflect.apply(h.trim, ...i); const j = (k, l.trim)(m);}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
 }
@@ -0,0 +1,175 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tests::{assert_not_modified, csi_receiver_from_str, rewrite_js_with_csi_methods},
+        visitor::csi_methods::CsiMethods,
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_symbol_table_shadowed_binding() -> Result<(), String> {
+        let original_code = "const cp = require('child_process');
+function run(cp, cmd) {
+    return cp.exec(cmd);
+}
+function runExec(exec, cmd) {
+    return exec(cmd);
+}
+const { exec } = cp;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_table_nested_require() -> Result<(), String> {
+        let original_code = "function run(cmd) {
+    const cp = require('child_process');
+    return cp.exec(cmd);
+}
+function other(cp, cmd) {
+    return cp.exec(cmd);
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("return (__datadog_test_0 = cp, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = cmd, _ddiast.childProcessExec(");
+        assert_that(&rewritten.code)
+            .contains("function other(cp, cmd) {\n    return cp.exec(cmd);");
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_table_alias() -> Result<(), String> {
+        let original_code = "const cp = require('child_process');
+const alias = cp;
+const { exec: run } = alias;
+alias.exec(cmd);
+run(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("__datadog_test_0 = alias, __datadog_test_1 = __datadog_test_0.exec, __datadog_test_2 = cmd, _ddiast.childProcessExec(");
+        assert_that(&rewritten.code).contains(
+            "_ddiast.childProcessExec(run(__datadog_test_0), run, undefined, __datadog_test_0));",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_table_reassignment() -> Result<(), String> {
+        let original_code = "let cp;
+cp = require('child_process');
+cp.exec(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("_ddiast.childProcessExec(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_table_conflicting_assignments() -> Result<(), String> {
+        let original_code = "let cp = require('child_process');
+if (mock) {
+    cp = require('./mock');
+}
+let run = require('child_process').exec;
+run += '';
+cp.exec(cmd);
+run(cmd);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_table_member_path() -> Result<(), String> {
+        let original_code = "const fs = require('fs');
+const { promises } = fs;
+promises.readFile(file);
+fs.readFile(file, cb);
+require('fs').promises.readFile(file);"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("promises.readFile(file);");
+        assert_that(&rewritten.code).contains("require('fs').promises.readFile(file);");
+        assert_that(&rewritten.code).contains("_ddiast.fsReadFile(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_symbol_table_typescript() -> Result<(), String> {
+        let original_code = "import { exec } from 'child_process';
+function run(cmd: string, exec: (cmd: string) => void): void {
+    exec(cmd);
+}
+exec(cmd as string);"
+            .to_string();
+        let js_file = "test.ts".to_string();
+        let methods = vec![
+            csi_receiver_from_str("exec", "childProcessExec", "child_process"),
+            csi_receiver_from_str("readFile", "fsReadFile", "fs"),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("exec(cmd);\n}");
+        assert_that(&rewritten.code).contains(
+            "_ddiast.childProcessExec(exec(__datadog_test_0), exec, undefined, __datadog_test_0));",
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        telemetry::Telemetry,
+        tests::{
+            assert_not_modified, csi_op_from_str, get_default_config, rewrite_js,
+            rewrite_js_with_config, rewrite_js_with_csi_methods,
+        },
+        visitor::csi_methods::CsiMethods,
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_tagged_template() -> Result<(), String> {
+        let original_code = "const q = sql`select ${a} from ${'t'}`;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("taggedTplOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const q = (__datadog_test_0 = a, __datadog_test_1 = ((strings)=>strings)`select ${__datadog_test_0} from ${'t'}`, _ddiast.taggedTplOperator(sql(__datadog_test_1, __datadog_test_0, 't'), sql, __datadog_test_1, __datadog_test_0, 't'));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tagged_template_string_raw() -> Result<(), String> {
+        let original_code = "const r = String.raw`C:\\\\${dir}\\n`;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("taggedTplOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const r = (__datadog_test_0 = String, __datadog_test_1 = __datadog_test_0.raw, __datadog_test_2 = dir, __datadog_test_3 = ((strings)=>strings)`C:\\\\${__datadog_test_2}\\n`, _ddiast.taggedTplOperator(__datadog_test_1.call(__datadog_test_0, __datadog_test_3, __datadog_test_2), __datadog_test_1, __datadog_test_3, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tagged_template_evaluated_tag() -> Result<(), String> {
+        let original_code = "const h = getTag()`<b>${a + b}</b>`;".to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![
+            csi_op_from_str("taggedTplOperator", None),
+            csi_op_from_str("plusOperator", None),
+        ];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_that(&rewritten.code).contains("const h = (__datadog_test_0 = getTag(), __datadog_test_1 = _ddiast.plusOperator(a + b, a, b), __datadog_test_2 = ((strings)=>strings)`<b>${__datadog_test_1}</b>`, _ddiast.taggedTplOperator(__datadog_test_0(__datadog_test_2, __datadog_test_1), __datadog_test_0, __datadog_test_2, __datadog_test_1));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_tagged_template_not_modified() -> Result<(), String> {
+        let original_code = "const a = html`<b></b>`;
+class A extends B {
+    m() {
+        return super.tag`${c}`;
+    }
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let methods = vec![csi_op_from_str("taggedTplOperator", None)];
+        let rewritten =
+            rewrite_js_with_csi_methods(original_code, js_file, &CsiMethods::new(&methods))
+                .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tagged_template_disabled() -> Result<(), String> {
+        let rewritten = rewrite_js(
+            "const q = sql`select ${a}`;".to_string(),
+            "test.js".to_string(),
+        )
+        .map_err(|e| e.to_string())?;
+
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tagged_template_telemetry_tag() -> Result<(), String> {
+        let mut config = get_default_config(false);
+        config.csi_methods = CsiMethods::new(&[csi_op_from_str("taggedTplOperator", None)]);
+        let rewritten = rewrite_js_with_config("const q = sql`${a}`;".to_string(), &config)
+            .map_err(|e| e.to_string())?;
+
+        let transform_status = rewritten.transform_status.ok_or("missing status")?;
+        let propagation_debug = transform_status
+            .telemetry
+            .get_propagation_debug()
+            .ok_or("missing debug telemetry")?;
+        assert_that(&propagation_debug.get("TaggedTpl")).is_equal_to(Some(&1));
+        Ok(())
+    }
+}
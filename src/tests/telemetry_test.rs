@@ -25,7 +25,7 @@ mod tests {
 
         let status = rewritten.transform_status.unwrap();
         assert_that(&status.status).is_equal_to(Status::Modified);
-        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(&1);
+        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(1);
         assert_that(&status.telemetry.get_propagation_debug()).is_some();
         let propagation_debug = status.telemetry.get_propagation_debug().unwrap();
         assert_that(&propagation_debug).contains_key(SUBSTRING.to_string());
@@ -46,7 +46,7 @@ mod tests {
 
         let status = rewritten.transform_status.unwrap();
         assert_that(&status.status).is_equal_to(Status::Modified);
-        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(&0);
+        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(0);
         assert_that(&status.telemetry.get_propagation_debug()).is_none();
 
         Ok(())
@@ -67,7 +67,7 @@ mod tests {
 
         let status = rewritten.transform_status.unwrap();
         assert_that(&status.status).is_equal_to(Status::Modified);
-        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(&1);
+        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(1);
         assert_that(&status.telemetry.get_propagation_debug()).is_none();
 
         Ok(())
@@ -84,7 +84,7 @@ mod tests {
 
         let status = rewritten.transform_status.unwrap();
         assert_that(&status.status).is_equal_to(Status::Modified);
-        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(&3);
+        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(3);
         assert_that(&status.telemetry.get_propagation_debug()).is_some();
         let propagation_debug = status.telemetry.get_propagation_debug().unwrap();
         assert_that(&propagation_debug).contains_key(SUBSTRING.to_string());
@@ -106,7 +106,7 @@ mod tests {
 
         let status = rewritten.transform_status.unwrap();
         assert_that(&status.status).is_equal_to(Status::NotModified);
-        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(&0);
+        assert_that(&status.telemetry.get_instrumented_propagation()).is_equal_to(0);
         assert_that(&status.telemetry.get_propagation_debug()).is_some();
         let propagation_debug = status.telemetry.get_propagation_debug().unwrap();
         assert_that(&propagation_debug).does_not_contain_key("not_configured_method".to_string());
@@ -6,7 +6,7 @@
 #[cfg(test)]
 mod tests {
 
-    use crate::tests::rewrite_js;
+    use crate::tests::{assert_not_modified, rewrite_js};
     use speculoos::{assert_that, string::StrAssertions};
 
     #[test]
@@ -141,4 +141,43 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_template_literal_with_literal_substitution_last() -> Result<(), String> {
+        let original_code = "{const a = `${b}${'x'}`}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = b, _ddiast.tplOperator(`${__datadog_test_0}${'x'}`, __datadog_test_0, 'x'));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_literal_with_literal_substitution_first() -> Result<(), String> {
+        let original_code = "{const a = `${1}-${userId}`}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = userId, _ddiast.tplOperator(`${1}-${__datadog_test_0}`, 1, __datadog_test_0));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_literal_with_mixed_substitutions() -> Result<(), String> {
+        let original_code = "{const a = `${null}:${b.c}:${true}:${d()}:${`e`}`}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .contains("const a = (__datadog_test_0 = b.c, __datadog_test_1 = d(), __datadog_test_2 = `e`, _ddiast.tplOperator(`${null}:${__datadog_test_0}:${true}:${__datadog_test_1}:${__datadog_test_2}`, null, __datadog_test_0, true, __datadog_test_1, __datadog_test_2));");
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_literal_with_only_literal_substitutions() -> Result<(), String> {
+        let original_code = "{const a = `${1}-${'x'}`; const b = `no substitutions`;}".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_not_modified(&rewritten);
+        Ok(())
+    }
 }
@@ -0,0 +1,136 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewriter::generate_prefix_stmts,
+        tests::{get_default_config, rewrite_js, rewrite_js_with_config},
+    };
+    use speculoos::{assert_that, string::StrAssertions};
+
+    #[test]
+    fn test_top_level_script_plus() -> Result<(), String> {
+        let original_code = "const q = 'SELECT ' + req.query.id;".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("var __datadog_test_0;
+const q = (__datadog_test_0 = req.query.id, _ddiast.plusOperator('SELECT ' + __datadog_test_0, 'SELECT ', __datadog_test_0));\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_script_without_temporal_vars() -> Result<(), String> {
+        let original_code = "const q = a + b;".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .is_equal_to("const q = _ddiast.plusOperator(a + b, a, b);\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_script_and_block() -> Result<(), String> {
+        let original_code = "const q = a.substring(1);
+function f() {
+    return `${b()}`;
+}"
+        .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("var __datadog_test_0, __datadog_test_1;
+const q = (__datadog_test_0 = a, __datadog_test_1 = __datadog_test_0.substring, _ddiast.stringSubstring(__datadog_test_1.call(__datadog_test_0, 1), __datadog_test_1, __datadog_test_0, 1));
+function f() {
+    let __datadog_test_0;
+    return (__datadog_test_0 = b(), _ddiast.tplOperator(`${__datadog_test_0}`, __datadog_test_0));
+}\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_script_comments() -> Result<(), String> {
+        let original_code = "// start
+function f() {}
+const s2 = a.trim();
+// end"
+            .to_string();
+        let rewritten = rewrite_js_with_config(original_code, &get_default_config(true))
+            .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .starts_with("// start\nvar __datadog_test_0, __datadog_test_1;\nfunction f() {}\n");
+        assert_that(&rewritten.code).ends_with("__datadog_test_0)); // end\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_script_use_strict() -> Result<(), String> {
+        let original_code = "'use strict'
+const q = 'SELECT ' + req.query.id;"
+            .to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).starts_with("'use strict';\nvar __datadog_test_0;\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_script_use_strict_and_prefix() -> Result<(), String> {
+        let original_code = "'use strict'
+const q = 'SELECT ' + req.query.id;"
+            .to_string();
+        let mut config = get_default_config(false);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, false, false);
+        let rewritten =
+            rewrite_js_with_config(original_code, &config).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .starts_with("'use strict';\n;\nif (typeof _ddiast === 'undefined')");
+        assert_that(&rewritten.code)
+            .contains("}((1, eval)('this')));\nvar __datadog_test_0;\nconst q = ");
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_module_after_imports() -> Result<(), String> {
+        let original_code = "import a from 'a';
+import { b } from 'b';
+export const q = a + b();
+export default `${a}x`;"
+            .to_string();
+        let js_file = "test.mjs".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("import a from 'a';
+import { b } from 'b';
+var __datadog_test_0, __datadog_test_1;
+export const q = (__datadog_test_0 = a, __datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));
+export default (__datadog_test_0 = a, _ddiast.tplOperator(`${__datadog_test_0}x`, __datadog_test_0));\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_module_use_strict_and_prefix() -> Result<(), String> {
+        let original_code = "'use strict'
+import a from 'a';
+export const q = `${a()}x`;"
+            .to_string();
+        let mut config = get_default_config(false);
+        config.file_prefix_code = generate_prefix_stmts(&config.csi_methods, false, false);
+        let rewritten =
+            rewrite_js_with_config(original_code, &config).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .starts_with("'use strict';\n;\nif (typeof _ddiast === 'undefined')");
+        assert_that(&rewritten.code).contains(
+            "}((1, eval)('this')));\nimport a from 'a';\nvar __datadog_test_0;\nexport const q = ",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_top_level_script_declared_variable() -> Result<(), String> {
+        let original_code = "const __datadog_test_0 = a + b();".to_string();
+        let js_file = "test.js".to_string();
+        let rewritten = rewrite_js(original_code, js_file).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).starts_with("var __datadog_test_1, __datadog_test_2;\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rewrite_error::{RewriteError, RewriteErrorCode},
+        rewriter::{RewrittenOutput, SyntaxMode},
+        tests::{get_default_config, rewrite_js},
+        transform::transform_status::Status,
+        util::DefaultFileReader,
+    };
+    use speculoos::{assert_that, option::OptionAssertions, string::StrAssertions};
+    use swc::sourcemap::SourceMap;
+
+    fn rewrite_ts(
+        code: &str,
+        file: &str,
+        syntax: SyntaxMode,
+        strip_types: bool,
+    ) -> Result<RewrittenOutput, RewriteError> {
+        let mut config = get_default_config(false);
+        config.syntax = syntax;
+        config.strip_types = strip_types;
+        crate::rewriter::rewrite_js(code.to_string(), file, &config, &DefaultFileReader {})
+    }
+
+    #[test]
+    fn test_typescript_keep_types() -> Result<(), String> {
+        let original_code = "function f(a: string, b: number): string {
+    return a + b;
+}"
+        .to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.ts".to_string()).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to(
+            "function f(a: string, b: number): string {
+    return _ddiast.plusOperator(a + b, a, b);
+}\n"
+            .to_string(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_strip_types() -> Result<(), String> {
+        let rewritten = rewrite_ts(
+            "function f(a: string, b: number): string {
+    return a + b;
+}",
+            "test.ts",
+            SyntaxMode::Auto,
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to(
+            "function f(a, b) {
+    return _ddiast.plusOperator(a + b, a, b);
+}\n"
+            .to_string(),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_type_assertions() -> Result<(), String> {
+        let original_code = "const a = (b as string) + c();".to_string();
+        let rewritten =
+            rewrite_js(original_code, "test.mts".to_string()).map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("var __datadog_test_0, __datadog_test_1;
+const a = (__datadog_test_0 = (b as string), __datadog_test_1 = c(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1));\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_strip_enum() -> Result<(), String> {
+        let rewritten = rewrite_ts(
+            "enum E { A = 'a' }
+interface I { x: string }
+const a: I = { x: b + c() };",
+            "test.ts",
+            SyntaxMode::Auto,
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("var __datadog_test_0, __datadog_test_1;
+var E;
+(function(E) {
+    E[\"A\"] = \"a\";
+})(E || (E = {}));
+const a = {
+    x: (__datadog_test_0 = b, __datadog_test_1 = c(), _ddiast.plusOperator(__datadog_test_0 + __datadog_test_1, __datadog_test_0, __datadog_test_1))
+};\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_strip_parameter_properties() -> Result<(), String> {
+        let rewritten = rewrite_ts(
+            "class A { constructor(private x: string) {} m() { return this.x.substring(1) } }",
+            "test.cts",
+            SyntaxMode::Auto,
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).contains("constructor(x){\n        this.x = x;\n    }");
+        assert_that(&rewritten.code).contains("_ddiast.stringSubstring(");
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_strip_types_not_instrumented() -> Result<(), String> {
+        let rewritten = rewrite_ts("const x: number = 1;", "test.ts", SyntaxMode::Auto, true)
+            .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code).is_equal_to("const x = 1;\n".to_string());
+        assert_that(&rewritten.transform_status.map(|status| status.status))
+            .is_some()
+            .is_equal_to(Status::Modified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_explicit_syntax() -> Result<(), String> {
+        let rewritten = rewrite_ts(
+            "const a: string = b + c;",
+            "test.js",
+            SyntaxMode::Typescript,
+            false,
+        )
+        .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.code)
+            .is_equal_to("const a: string = _ddiast.plusOperator(b + c, b, c);\n".to_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_explicit_ecmascript_syntax() {
+        let result = rewrite_ts(
+            "const a: string = b + c;",
+            "test.ts",
+            SyntaxMode::Ecmascript,
+            false,
+        );
+        assert_that(&result.err().map(|e| e.code))
+            .is_some()
+            .is_equal_to(RewriteErrorCode::ParseError);
+    }
+
+    #[test]
+    fn test_typescript_strip_types_ignored_in_js() -> Result<(), String> {
+        let rewritten = rewrite_ts("const a = 1;", "test.js", SyntaxMode::Auto, true)
+            .map_err(|e| e.to_string())?;
+        assert_that(&rewritten.transform_status.map(|status| status.status))
+            .is_some()
+            .is_equal_to(Status::NotModified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typescript_source_map() -> Result<(), String> {
+        let rewritten = rewrite_ts(
+            "function f(a: string, b: number): string {
+    return a + b;
+}",
+            "test.ts",
+            SyntaxMode::Auto,
+            true,
+        )
+        .map_err(|e| e.to_string())?;
+        let source_map =
+            SourceMap::from_slice(rewritten.source_map.as_bytes()).map_err(|e| e.to_string())?;
+        let dst_col = rewritten
+            .code
+            .lines()
+            .nth(1)
+            .unwrap_or_default()
+            .find("a + b");
+        let token = dst_col.and_then(|col| source_map.lookup_token(1, col as u32));
+
+        assert_that(&source_map.get_source(0))
+            .is_some()
+            .is_equal_to("test.ts");
+        assert_that(&token.map(|token| (token.get_src_line(), token.get_src_col())))
+            .is_some()
+            .is_equal_to((1, 11));
+        Ok(())
+    }
+}
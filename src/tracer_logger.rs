@@ -7,10 +7,21 @@ use std::{
     str::FromStr,
     sync::atomic::{AtomicBool, Ordering},
 };
+
+#[cfg(not(feature = "napi"))]
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
+#[cfg(feature = "napi")]
+use napi::{
+    threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
+    Env, JsFunction, JsObject, JsUnknown, ValueType,
+};
+#[cfg(feature = "napi")]
+use std::sync::RwLock;
+
 type LoggerFn<'a> = &'a (dyn Fn(&str, String) + Send + Sync);
 
+#[cfg(not(feature = "napi"))]
 #[wasm_bindgen(module = "/tracer_logger.js")]
 extern "C" {
     #[wasm_bindgen (js_name = log, catch)]
@@ -29,11 +40,19 @@ impl<'a> TracerLogger<'a> {
         TracerLogger { logger }
     }
 
+    #[cfg(not(feature = "napi"))]
     pub fn default() -> Self {
         Self::new(&|level, msg| {
             log(&level.into(), &msg.into()).ok();
         })
     }
+
+    #[cfg(feature = "napi")]
+    pub fn default() -> Self {
+        Self::new(&|level, msg| {
+            log_native(level, msg);
+        })
+    }
 }
 
 impl Log for TracerLogger<'_> {
@@ -52,6 +71,7 @@ impl Log for TracerLogger<'_> {
 
 static LOGGER_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+#[cfg(not(feature = "napi"))]
 pub fn set_logger(logger: &JsValue, level: &str) -> anyhow::Result<JsValue, JsValue> {
     if !LOGGER_INITIALIZED.load(Ordering::Relaxed) {
         log::set_boxed_logger(Box::new(TracerLogger::default()))
@@ -63,7 +83,75 @@ pub fn set_logger(logger: &JsValue, level: &str) -> anyhow::Result<JsValue, JsVa
     }
 }
 
+#[cfg(not(feature = "napi"))]
 fn set_logger_and_level(logger: &JsValue, level: &str) -> anyhow::Result<JsValue, JsValue> {
     log::set_max_level(LevelFilter::from_str(level).unwrap_or(log::max_level()));
     setLogger(logger)
 }
+
+// Native addon: every level of the js logger is wrapped in a threadsafe function so it can be
+// called from the global `log` logger. Calls are queued in the js event loop.
+#[cfg(feature = "napi")]
+type LogFunction = ThreadsafeFunction<String, ErrorStrategy::Fatal>;
+
+#[cfg(feature = "napi")]
+static LOG_FUNCTIONS: RwLock<Vec<(&str, LogFunction)>> = RwLock::new(Vec::new());
+
+#[cfg(feature = "napi")]
+const LOG_LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+#[cfg(feature = "napi")]
+fn log_native(level: &str, msg: String) {
+    if let Ok(log_functions) = LOG_FUNCTIONS.read() {
+        let level = level.to_lowercase();
+        if let Some((_, log_fn)) = log_functions.iter().find(|(name, _)| *name == level) {
+            log_fn.call(msg, ThreadsafeFunctionCallMode::NonBlocking);
+        }
+    }
+}
+
+#[cfg(feature = "napi")]
+pub fn set_logger(env: &Env, logger: Option<JsObject>, level: &str) -> napi::Result<()> {
+    if !LOGGER_INITIALIZED.load(Ordering::Relaxed) {
+        log::set_boxed_logger(Box::new(TracerLogger::default()))
+            .map(|_| LOGGER_INITIALIZED.store(true, Ordering::Relaxed))
+            .map_err(|err| napi::Error::from_reason(format!("{err:?}")))?;
+    }
+    set_logger_and_level(env, logger, level)
+}
+
+#[cfg(feature = "napi")]
+fn set_logger_and_level(env: &Env, logger: Option<JsObject>, level: &str) -> napi::Result<()> {
+    log::set_max_level(LevelFilter::from_str(level).unwrap_or(log::max_level()));
+
+    let logger = match logger {
+        Some(logger) => logger,
+        None => env
+            .get_global()?
+            .get_named_property::<JsObject>("console")?,
+    };
+
+    let mut log_functions = Vec::new();
+    for name in LOG_LEVELS {
+        let log_fn = logger.get_named_property::<JsUnknown>(name)?;
+        if log_fn.get_type()? != ValueType::Function {
+            continue;
+        }
+
+        // bound to the logger so methods relying on `this` keep working when called natively
+        let log_fn = log_fn.coerce_to_object()?;
+        let bind = log_fn.get_named_property::<JsFunction>("bind")?;
+        let log_fn: JsFunction = unsafe { bind.call(Some(&log_fn), &[&logger])?.cast() };
+        let mut tsfn: LogFunction =
+            log_fn.create_threadsafe_function(0, |ctx| Ok(vec![ctx.value]))?;
+
+        // logging must not keep the process alive
+        tsfn.unref(env)?;
+        log_functions.push((name, tsfn));
+    }
+
+    *LOG_FUNCTIONS
+        .write()
+        .map_err(|err| napi::Error::from_reason(format!("{err:?}")))? = log_functions;
+    Ok(())
+}
@@ -2,12 +2,16 @@
 * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
 * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
 **/
+use swc_common::Span;
 use swc_ecma_ast::*;
 use swc_ecma_visit::VisitMutWith;
 
 use crate::{
     transform::assign_add_transform::AssignOp::Assign,
-    visitor::operation_transform_visitor::OperationTransformVisitor,
+    visitor::{
+        ident_provider::{IdentKind, IdentProvider},
+        operation_transform_visitor::OperationTransformVisitor,
+    },
 };
 
 use super::{binary_add_transform::BinaryAddTransform, transform_status::TransformResult};
@@ -28,10 +32,26 @@ impl AssignAddTransform {
             }
 
             AssignTarget::Simple(left_expr) => {
+                // the object and the key of the target are evaluated once
+                let stored_member = match left_expr {
+                    SimpleAssignTarget::Member(member) => {
+                        get_stored_member(member, &span, opv.ident_provider)
+                    }
+                    _ => None,
+                };
+
+                let (left, read_expr) = match stored_member {
+                    Some((target, read)) => (
+                        AssignTarget::Simple(SimpleAssignTarget::Member(target)),
+                        Box::new(Expr::Member(read)),
+                    ),
+                    None => (assign.left.clone(), left_expr.clone().into()),
+                };
+
                 let binary = Expr::Bin(BinExpr {
                     span,
                     op: BinaryOp::Add,
-                    left: left_expr.clone().into(),
+                    left: read_expr,
                     right: assign.right.clone(),
                 });
 
@@ -44,7 +64,7 @@ impl AssignAddTransform {
                     let new_assign = AssignExpr {
                         span,
                         op: Assign,
-                        left: assign.left.clone(),
+                        left,
                         right: Box::new(result.expr.unwrap()),
                     };
                     TransformResult::modified(new_assign)
@@ -55,3 +75,60 @@ impl AssignAddTransform {
         }
     }
 }
+
+// getObj()[key()] += c -> (__datadog_token_$i = getObj())[__datadog_token_$i2 = key()] = ...
+// returns the target storing the object and the key and the member reading them from the temporal
+// vars, none when both are identifiers or literals
+fn get_stored_member(
+    member: &MemberExpr,
+    span: &Span,
+    ident_provider: &mut dyn IdentProvider,
+) -> Option<(MemberExpr, MemberExpr)> {
+    let mut target = member.clone();
+    let mut read = member.clone();
+    let mut stored = false;
+
+    if let Some((assignation, ident)) = store_operand(&member.obj, span, ident_provider) {
+        target.obj = Box::new(Expr::Paren(ParenExpr {
+            span: *span,
+            expr: Box::new(assignation),
+        }));
+        read.obj = Box::new(Expr::Ident(ident));
+        stored = true;
+    }
+
+    if let MemberProp::Computed(computed) = &member.prop {
+        if let Some((assignation, ident)) = store_operand(&computed.expr, span, ident_provider) {
+            target.prop = MemberProp::Computed(ComputedPropName {
+                span: computed.span,
+                expr: Box::new(assignation),
+            });
+            read.prop = MemberProp::Computed(ComputedPropName {
+                span: computed.span,
+                expr: Box::new(Expr::Ident(ident)),
+            });
+            stored = true;
+        }
+    }
+
+    stored.then_some((target, read))
+}
+
+fn store_operand(
+    operand: &Expr,
+    span: &Span,
+    ident_provider: &mut dyn IdentProvider,
+) -> Option<(Expr, Ident)> {
+    if matches!(operand, Expr::Ident(_) | Expr::This(_) | Expr::Lit(_)) {
+        return None;
+    }
+
+    let mut assignations = Vec::new();
+    let ident = ident_provider.get_temporal_ident_used_in_assignation(
+        operand,
+        &mut assignations,
+        span,
+        IdentKind::Expr,
+    )?;
+    assignations.pop().map(|assignation| (assignation, ident))
+}
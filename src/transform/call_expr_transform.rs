@@ -3,27 +3,30 @@
  * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
  **/
 use swc::atoms::JsWord;
-use swc_common::SyntaxContext;
+use swc_common::{Span, SyntaxContext};
 use swc_ecma_ast::*;
 
 use crate::{
     transform::{
-        function_prototype_transform::FunctionPrototypeTransform,
+        function_prototype_transform::{FunctionPrototypeTransform, APPLY_METHOD_NAME, PROTOTYPE},
         operand_handler::{DefaultOperandHandler, OperandHandler},
     },
     visitor::{
-        csi_methods::CsiMethods,
+        csi_methods::{CsiMethod, CsiMethodKind, CsiMethods},
         ident_provider::{IdentKind, IdentProvider},
+        symbol_table::{ModuleRef, PrototypeMethod, SymbolTable},
     },
 };
 
-use crate::visitor::visitor_util::get_dd_paren_expr;
+use crate::visitor::visitor_util::{get_dd_call_expr, get_dd_paren_expr};
 
 use super::{
     operand_handler::{ExpandArrays, IdentMode},
     transform_status::TransformResult,
 };
 
+const REFLECT: &str = "Reflect";
+
 pub struct ResultExpr {
     pub expr: Expr,
     pub tag: String,
@@ -35,99 +38,135 @@ impl CallExprTransform {
     pub fn to_dd_call_expr(
         call: &mut CallExpr,
         csi_methods: &CsiMethods,
+        symbol_table: &SymbolTable,
         ident_provider: &mut dyn IdentProvider,
     ) -> TransformResult<Expr> {
         let callee = call.callee.clone();
         let optional_result_expr = match callee {
             Callee::Expr(expr) => match *expr {
-                Expr::Member(member) => match (*member.obj, &member.prop) {
-                    // replace ident and call members, exclude literal "".substring() calls but do not exclude "literal".concat(a, b, c)
-                    (Expr::Lit(literal), MemberProp::Ident(ident)) => {
-                        if csi_methods.method_allows_literal_callers(&ident.sym) {
-                            replace_call_expr_if_csi_method(
-                                &Expr::Lit(literal),
-                                ident,
-                                call,
-                                csi_methods,
-                                ident_provider,
-                            )
-                        } else {
-                            None
-                        }
-                    }
-
-                    (Expr::Ident(obj), MemberProp::Ident(ident)) => {
-                        replace_call_expr_if_csi_method(
-                            &Expr::Ident(obj),
-                            ident,
-                            call,
-                            csi_methods,
-                            ident_provider,
-                        )
-                    }
-
-                    (Expr::Call(callee_call), MemberProp::Ident(ident)) => {
-                        replace_call_expr_if_csi_method(
-                            &Expr::Call(callee_call),
-                            ident,
-                            call,
+                // Reflect.apply(String.prototype.concat, a, [b]) as String.prototype.concat.apply(a, [b])
+                Expr::Member(member) if is_reflect_apply(&member) => {
+                    return match get_reflect_apply_call(call) {
+                        Some(mut apply_call) => Self::to_dd_call_expr(
+                            &mut apply_call,
                             csi_methods,
+                            symbol_table,
                             ident_provider,
-                        )
-                    }
+                        ),
+                        None => TransformResult::not_modified(),
+                    };
+                }
+
+                Expr::Member(member) => {
+                    // require('child_process').exec() or cp.exec() with cp bound to require('child_process')
+                    let module_ref = symbol_table.resolve(&member.obj);
+                    let receiver = module_ref.as_ref().and_then(ModuleRef::namespace);
+                    // a.substring() or a['substring']() with a literal key
+                    let prop = get_static_prop_name(&member.prop);
+                    match (&*member.obj, prop.as_ref()) {
+                        // replace ident and call members, exclude literal "".substring() calls but do not exclude "literal".concat(a, b, c)
+                        (Expr::Lit(_), Some(ident)) => {
+                            if csi_methods.method_allows_literal_callers(&ident.sym) {
+                                replace_call_expr_if_csi_method(
+                                    &member.obj,
+                                    ident,
+                                    call,
+                                    csi_methods,
+                                    None,
+                                    ident_provider,
+                                )
+                            } else {
+                                None
+                            }
+                        }
 
-                    (Expr::Paren(paren), MemberProp::Ident(ident)) => {
-                        replace_call_expr_if_csi_method(
-                            &Expr::Paren(paren),
-                            ident,
-                            call,
-                            csi_methods,
-                            ident_provider,
-                        )
-                    }
+                        (Expr::Ident(_), Some(ident))
+                            if FunctionPrototypeTransform::is_call_or_apply(ident) =>
+                        {
+                            match symbol_table
+                                .resolve_prototype_method(&member.obj)
+                                .filter(|prototype_method| prototype_method.bound_this.is_none())
+                            {
+                                Some(prototype_method) => replace_stored_prototype_call_or_apply(
+                                    call,
+                                    &member.obj,
+                                    &prototype_method,
+                                    ident,
+                                    csi_methods,
+                                    ident_provider,
+                                ),
+                                None => replace_call_expr_if_csi_method(
+                                    &member.obj,
+                                    ident,
+                                    call,
+                                    csi_methods,
+                                    receiver,
+                                    ident_provider,
+                                ),
+                            }
+                        }
 
-                    (Expr::Array(paren), MemberProp::Ident(ident)) => {
-                        replace_call_expr_if_csi_method(
-                            &Expr::Array(paren),
+                        (
+                            Expr::Ident(_) | Expr::Call(_) | Expr::Paren(_) | Expr::Array(_),
+                            Some(ident),
+                        ) => replace_call_expr_if_csi_method(
+                            &member.obj,
                             ident,
                             call,
                             csi_methods,
+                            receiver,
                             ident_provider,
-                        )
-                    }
-
-                    (Expr::Member(member_obj), MemberProp::Ident(ident)) => {
-                        // may be something like String.prototype.substring.call
-                        if FunctionPrototypeTransform::is_call_or_apply(ident) {
-                            replace_prototype_call_or_apply(
-                                call,
-                                &member_obj,
-                                ident,
-                                csi_methods,
-                                ident_provider,
-                            )
-
-                        // or a.b.substring() but not String.prototype.substring()
-                        } else if !FunctionPrototypeTransform::member_prop_is_prototype(&member_obj)
-                        {
-                            replace_call_expr_if_csi_method(
-                                &Expr::Member(member_obj),
-                                ident,
-                                call,
-                                csi_methods,
-                                ident_provider,
-                            )
-                        } else {
-                            None
+                        ),
+
+                        (Expr::Member(member_obj), Some(ident)) => {
+                            // may be something like String.prototype.substring.call
+                            if FunctionPrototypeTransform::is_call_or_apply(ident) {
+                                replace_prototype_call_or_apply(
+                                    call,
+                                    member_obj,
+                                    ident,
+                                    csi_methods,
+                                    ident_provider,
+                                )
+
+                            // or a.b.substring() but not String.prototype.substring()
+                            } else if !FunctionPrototypeTransform::member_prop_is_prototype(
+                                member_obj,
+                            ) {
+                                replace_call_expr_if_csi_method(
+                                    &member.obj,
+                                    ident,
+                                    call,
+                                    csi_methods,
+                                    None,
+                                    ident_provider,
+                                )
+                            } else {
+                                None
+                            }
                         }
+                        _ => None,
                     }
-                    _ => None,
+                }
+
+                // (0, obj.fn)(a) emitted by transpilers to call an imported function
+                Expr::Paren(paren) => match get_indirect_callee(&paren) {
+                    Some((member, ident)) => replace_indirect_call_if_csi_method(
+                        member,
+                        &ident,
+                        call,
+                        csi_methods,
+                        symbol_table,
+                        ident_provider,
+                    ),
+                    None => None,
                 },
 
                 Expr::Ident(obj) => replace_call_expr_if_csi_method_without_callee(
                     &obj,
                     call,
                     csi_methods,
+                    symbol_table,
                     ident_provider,
                 ),
                 _ => None,
@@ -143,6 +182,68 @@ impl CallExprTransform {
     }
 }
 
+// name of an ident prop or of a computed prop with a string or template literal key which is a
+// valid identifier, so the rewritten member can be accessed with a dot
+pub fn get_static_prop_name(prop: &MemberProp) -> Option<IdentName> {
+    match prop {
+        MemberProp::Ident(ident) => Some(ident.clone()),
+        MemberProp::Computed(ComputedPropName { span, expr }) => {
+            let name = match &**expr {
+                Expr::Lit(Lit::Str(str)) => Some(str.value.clone()),
+                Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+                    tpl.quasis.first().and_then(|quasi| quasi.cooked.clone())
+                }
+                _ => None,
+            }?;
+            Ident::verify_symbol(&name)
+                .ok()
+                .map(|_| IdentName::new(name, *span))
+        }
+        MemberProp::PrivateName(_) => None,
+    }
+}
+
+// obj.fn of a (0, obj.fn) callee
+fn get_indirect_callee(paren: &ParenExpr) -> Option<(&MemberExpr, IdentName)> {
+    match &*paren.expr {
+        Expr::Seq(SeqExpr { exprs, .. }) => match exprs.as_slice() {
+            [first, callee] if first.is_lit() => match &**callee {
+                Expr::Member(member) => {
+                    get_static_prop_name(&member.prop).map(|ident| (member, ident))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_reflect_apply(member: &MemberExpr) -> bool {
+    member.obj.is_ident_ref_to(REFLECT)
+        && get_static_prop_name(&member.prop).is_some_and(|prop| prop.sym == APPLY_METHOD_NAME)
+}
+
+// Reflect.apply(target, this, args) -> target.apply(this, args)
+fn get_reflect_apply_call(call: &CallExpr) -> Option<CallExpr> {
+    match call.args.as_slice() {
+        [target, this_arg, args]
+            if call.args.iter().all(|arg| arg.spread.is_none()) && target.expr.is_member() =>
+        {
+            Some(CallExpr {
+                callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                    span: call.span,
+                    obj: target.expr.clone(),
+                    prop: MemberProp::Ident(IdentName::new(APPLY_METHOD_NAME.into(), call.span)),
+                }))),
+                args: vec![this_arg.clone(), args.clone()],
+                ..call.clone()
+            })
+        }
+        _ => None,
+    }
+}
+
 fn replace_prototype_call_or_apply(
     call: &CallExpr,
     member: &MemberExpr,
@@ -163,7 +264,43 @@ fn replace_prototype_call_or_apply(
             &prototype_call.1.into(),
             &mut prototype_call.2,
             csi_methods,
-            member,
+            &Expr::Member(member.clone()),
+            &ident_name.sym,
+            ident_provider,
+        ),
+        _ => None,
+    }
+}
+
+// concat.call(a, b) with concat bound to String.prototype.concat
+fn replace_stored_prototype_call_or_apply(
+    call: &CallExpr,
+    callee: &Expr,
+    prototype_method: &PrototypeMethod,
+    ident_name: &IdentName,
+    csi_methods: &CsiMethods,
+    ident_provider: &mut dyn IdentProvider,
+) -> Option<ResultExpr> {
+    let method_ident = Ident::new(
+        prototype_method.method.clone().into(),
+        call.span,
+        SyntaxContext::empty(),
+    );
+    let prototype_call_option =
+        FunctionPrototypeTransform::get_expression_parts_from_method_call_or_apply(
+            call,
+            method_ident,
+            ident_name,
+            csi_methods,
+        );
+
+    match prototype_call_option {
+        Some(mut prototype_call) => replace_call_expr_or_spread_if_csi_method_with_member(
+            &prototype_call.0,
+            &prototype_call.1.into(),
+            &mut prototype_call.2,
+            csi_methods,
+            callee,
             &ident_name.sym,
             ident_provider,
         ),
@@ -176,13 +313,15 @@ fn replace_call_expr_if_csi_method(
     ident_name: &IdentName,
     call: &mut CallExpr,
     csi_methods: &CsiMethods,
+    receiver: Option<&str>,
     ident_provider: &mut dyn IdentProvider,
 ) -> Option<ResultExpr> {
+    let csi_method = csi_methods.get_with_receiver(&ident_name.sym, receiver)?;
     replace_call_expr_if_csi_method_with_member(
         expr,
         ident_name,
         call,
-        csi_methods,
+        csi_method,
         None,
         None,
         ident_provider,
@@ -193,144 +332,223 @@ fn replace_call_expr_if_csi_method_without_callee(
     ident: &Ident,
     call: &mut CallExpr,
     csi_methods: &CsiMethods,
+    symbol_table: &SymbolTable,
     ident_provider: &mut dyn IdentProvider,
 ) -> Option<ResultExpr> {
-    let method_name = &ident.sym.to_string();
-
-    if let Some(csi_method) = csi_methods.get(method_name) {
-        if csi_method.allowed_without_callee {
-            let mut assignations = Vec::new();
-            let mut arguments = Vec::new();
-            let span = call.span;
-
-            // let __datadog_test_0;
-            // (__datadog_test_0 = arg0, _ddiast.aloneMethod
-            // (aloneMethod(__datadog_test_0), aloneMethod, undefined, __datadog_test_0));
-            arguments.push(ExprOrSpread::from(Expr::Ident(ident.clone())));
-            let global = Ident {
-                span,
-                sym: JsWord::from("undefined"),
-                optional: false,
-                ctxt: SyntaxContext::empty(),
+    let span = call.span;
+
+    // sub(a) with sub bound to String.prototype.substring.bind(s) is reported as a call of
+    // String.prototype.substring on s
+    let bound_method = symbol_table
+        .resolve_prototype_method(&Expr::Ident(ident.clone()))
+        .and_then(|prototype_method| {
+            let this = prototype_method.bound_this.clone()?;
+            let csi_method = csi_methods.get(&prototype_method.method)?;
+            Some((
+                csi_method,
+                prototype_method_expr(&prototype_method, &span),
+                Expr::Ident(this),
+            ))
+        });
+
+    let (csi_method, fn_expr, this_expr) = match bound_method {
+        Some(bound_method) => bound_method,
+        None => {
+            // exec() with exec bound to require('child_process').exec
+            let module_ref = symbol_table.resolve(&Expr::Ident(ident.clone()));
+            let qualified_method = module_ref
+                .as_ref()
+                .and_then(ModuleRef::export)
+                .and_then(|(module, export)| csi_methods.get_qualified(export, module));
+
+            let unqualified_method = || {
+                csi_methods
+                    .get(&ident.sym)
+                    .filter(|csi_method| csi_method.allowed_without_callee)
             };
-            arguments.push(ExprOrSpread::from(Expr::Ident(global)));
 
-            let call_replacement = replace_call_callee_and_args(
-                call,
-                None,
-                &mut assignations,
-                &mut arguments,
-                None,
-                ident_provider,
-            );
-
-            return Some(ResultExpr {
-                tag: method_name.clone(),
-                expr: get_dd_paren_expr(
-                    &Expr::Call(call_replacement),
-                    &arguments,
-                    &mut assignations,
-                    csi_method.dst.as_str(),
-                    &span,
-                ),
-            });
+            let csi_method = qualified_method.or_else(unqualified_method)?;
+            (
+                csi_method,
+                Expr::Ident(ident.clone()),
+                undefined_expr(&span),
+            )
         }
-    }
-    None
+    };
+
+    let mut assignations = Vec::new();
+    let mut arguments = Vec::new();
+
+    // let __datadog_test_0;
+    // (__datadog_test_0 = arg0, _ddiast.aloneMethod
+    // (aloneMethod(__datadog_test_0), aloneMethod, undefined, __datadog_test_0));
+    arguments.push(ExprOrSpread::from(fn_expr));
+    arguments.push(ExprOrSpread::from(this_expr));
+
+    let call_replacement = replace_call_callee_and_args(
+        call,
+        None,
+        &mut assignations,
+        &mut arguments,
+        None,
+        ident_provider,
+    );
+
+    Some(ResultExpr {
+        tag: csi_method.src.clone(),
+        expr: get_hook_expr(
+            csi_method,
+            call_replacement,
+            &arguments,
+            &mut assignations,
+            &span,
+            ident_provider,
+        ),
+    })
 }
 
-fn replace_call_expr_if_csi_method_with_member(
-    expr: &Expr,
+fn replace_indirect_call_if_csi_method(
+    member: &MemberExpr,
     ident_name: &IdentName,
     call: &mut CallExpr,
     csi_methods: &CsiMethods,
-    member_expr_opt: Option<&MemberExpr>,
-    call_or_apply: Option<&str>,
+    symbol_table: &SymbolTable,
     ident_provider: &mut dyn IdentProvider,
 ) -> Option<ResultExpr> {
-    let method_name = &ident_name.sym.to_string();
+    let module_ref = symbol_table.resolve(&member.obj);
+    let receiver = module_ref.as_ref().and_then(ModuleRef::namespace);
+    let csi_method = csi_methods.get_with_receiver(&ident_name.sym, receiver)?;
 
-    if let Some(csi_method) = csi_methods.get(method_name) {
-        let mut assignations = Vec::new();
-        let mut arguments = Vec::new();
-        let span = call.span;
+    let mut assignations = Vec::new();
+    let mut arguments = Vec::new();
+    let span = call.span;
 
-        // replace original call expression with a parent expression splitting every component and finally invoking .call
-        //  a) a.substring() -> __datadog_token_$i = a, __datadog_token_$i2 = __datadog_token_$i.substring, __datadog_token_$i2.call(__datadog_token_$i, __datadog_token_$i2)
-        //  b) String.prototype.substring.[call|apply](a) -> __datadog_token_$i = a, __datadog_token_$i2 = String.prototype.substring, __datadog_token_$i2.call(__datadog_token_$i, __datadog_token_$i2)
+    // the function is invoked without receiver so it is stored and called as is
+    //  (0, a.b)(c) -> __datadog_token_$i = a.b, __datadog_token_$i2 = c,
+    //      _ddiast.b(__datadog_token_$i(__datadog_token_$i2), __datadog_token_$i, undefined, __datadog_token_$i2)
+    let ident_callee = ident_provider.get_ident_used_in_assignation(
+        &Expr::Member(member.clone()),
+        &mut assignations,
+        &mut arguments,
+        &span,
+        IdentKind::Expr,
+    )?;
+    arguments.push(ExprOrSpread::from(undefined_expr(&span)));
+
+    let mut indirect_call = call.clone();
+    indirect_call.callee = Callee::Expr(Box::new(Expr::Ident(ident_callee)));
+    let call_replacement = replace_call_callee_and_args(
+        &mut indirect_call,
+        None,
+        &mut assignations,
+        &mut arguments,
+        None,
+        ident_provider,
+    );
 
-        // __datadog_token_$i = a
-        let ident_replacement_option = ident_provider.get_temporal_ident_used_in_assignation(
-            expr,
+    Some(ResultExpr {
+        tag: csi_method.src.clone(),
+        expr: get_hook_expr(
+            csi_method,
+            call_replacement,
+            &arguments,
             &mut assignations,
             &span,
-            IdentKind::Expr,
-        );
+            ident_provider,
+        ),
+    })
+}
 
-        let ident_replacement = ident_replacement_option.map_or_else(|| expr.clone(), Expr::Ident);
-
-        let ident_callee = match member_expr_opt {
-            Some(member_expr) => {
-                // __datadog_token_$i2 = member
-                ident_provider.get_ident_used_in_assignation(
-                    &Expr::Member(member_expr.clone()),
-                    &mut assignations,
-                    &mut arguments,
-                    &span,
-                    IdentKind::Expr,
-                )
-            }
-            None => {
-                // __datadog_token_$i.substring
-                let member_expr = MemberExpr {
-                    span,
-                    obj: Box::new(ident_replacement.clone()),
-                    prop: MemberProp::Ident(ident_name.clone()),
-                };
-
-                // __datadog_token_$i2 = __datadog_token_$i.substring
-                ident_provider.get_ident_used_in_assignation(
-                    &Expr::Member(member_expr),
-                    &mut assignations,
-                    &mut arguments,
-                    &span,
-                    IdentKind::Expr,
-                )
-            }
-        };
+fn replace_call_expr_if_csi_method_with_member(
+    expr: &Expr,
+    ident_name: &IdentName,
+    call: &mut CallExpr,
+    csi_method: &CsiMethod,
+    callee_expr_opt: Option<&Expr>,
+    call_or_apply: Option<&str>,
+    ident_provider: &mut dyn IdentProvider,
+) -> Option<ResultExpr> {
+    let method_name = &ident_name.sym.to_string();
 
-        arguments.push(ExprOrSpread::from(ident_replacement.clone()));
+    let mut assignations = Vec::new();
+    let mut arguments = Vec::new();
+    let span = call.span;
 
-        let mut call_replacement = replace_call_callee_and_args(
-            call,
-            Some(ident_callee.map_or_else(|| expr.clone(), Expr::Ident)),
-            &mut assignations,
-            &mut arguments,
-            call_or_apply,
-            ident_provider,
-        );
+    // replace original call expression with a parent expression splitting every component and finally invoking .call
+    //  a) a.substring() -> __datadog_token_$i = a, __datadog_token_$i2 = __datadog_token_$i.substring, __datadog_token_$i2.call(__datadog_token_$i, __datadog_token_$i2)
+    //  b) String.prototype.substring.[call|apply](a) -> __datadog_token_$i = a, __datadog_token_$i2 = String.prototype.substring, __datadog_token_$i2.call(__datadog_token_$i, __datadog_token_$i2)
 
-        // insert .call(this) argument
-        call_replacement.args.insert(
-            0,
-            ExprOrSpread {
-                spread: None,
-                expr: Box::new(ident_replacement),
-            },
-        );
+    // __datadog_token_$i = a
+    let ident_replacement_option = ident_provider.get_temporal_ident_used_in_assignation(
+        expr,
+        &mut assignations,
+        &span,
+        IdentKind::Expr,
+    );
 
-        return Some(ResultExpr {
-            tag: method_name.clone(),
-            expr: get_dd_paren_expr(
-                &Expr::Call(call_replacement),
-                &arguments,
+    let ident_replacement = ident_replacement_option.map_or_else(|| expr.clone(), Expr::Ident);
+
+    let ident_callee = match callee_expr_opt {
+        Some(callee_expr) => {
+            // __datadog_token_$i2 = member
+            ident_provider.get_ident_used_in_assignation(
+                callee_expr,
                 &mut assignations,
-                csi_method.dst.as_str(),
+                &mut arguments,
                 &span,
-            ),
-        });
-    }
-    None
+                IdentKind::Expr,
+            )
+        }
+        None => {
+            // __datadog_token_$i.substring
+            let member_expr = MemberExpr {
+                span,
+                obj: Box::new(ident_replacement.clone()),
+                prop: MemberProp::Ident(ident_name.clone()),
+            };
+
+            // __datadog_token_$i2 = __datadog_token_$i.substring
+            ident_provider.get_ident_used_in_assignation(
+                &Expr::Member(member_expr),
+                &mut assignations,
+                &mut arguments,
+                &span,
+                IdentKind::Expr,
+            )
+        }
+    };
+
+    arguments.push(ExprOrSpread::from(ident_replacement.clone()));
+
+    let mut call_replacement = replace_call_callee_and_args(
+        call,
+        Some(ident_callee.map_or_else(|| expr.clone(), Expr::Ident)),
+        &mut assignations,
+        &mut arguments,
+        call_or_apply,
+        ident_provider,
+    );
+
+    // insert .call(this) argument
+    call_replacement.args.insert(
+        0,
+        ExprOrSpread {
+            spread: None,
+            expr: Box::new(ident_replacement),
+        },
+    );
+
+    Some(ResultExpr {
+        tag: method_name.clone(),
+        expr: get_hook_expr(
+            csi_method,
+            call_replacement,
+            &arguments,
+            &mut assignations,
+            &span,
+            ident_provider,
+        ),
+    })
 }
 
 fn replace_call_expr_or_spread_if_csi_method_with_member(
@@ -338,18 +556,19 @@ fn replace_call_expr_or_spread_if_csi_method_with_member(
     ident_name: &IdentName,
     call: &mut CallExpr,
     csi_methods: &CsiMethods,
-    member_expr: &MemberExpr,
+    callee_expr: &Expr,
     call_or_apply: &str,
     ident_provider: &mut dyn IdentProvider,
 ) -> Option<ResultExpr> {
+    let csi_method = csi_methods.get(&ident_name.sym)?;
     if expr_or_spread.spread.is_none() {
         //  String.prototype.concat.call(a, b) or a.concat(b)
         replace_call_expr_if_csi_method_with_member(
             &expr_or_spread.expr,
             ident_name,
             call,
-            csi_methods,
-            Some(member_expr),
+            csi_method,
+            Some(callee_expr),
             Some(call_or_apply),
             ident_provider,
         )
@@ -358,8 +577,8 @@ fn replace_call_expr_or_spread_if_csi_method_with_member(
         replace_call_spread_if_csi_method_with_member(
             ident_name,
             call,
-            csi_methods,
-            member_expr,
+            csi_method,
+            callee_expr,
             call_or_apply,
             ident_provider,
         )
@@ -369,51 +588,49 @@ fn replace_call_expr_or_spread_if_csi_method_with_member(
 fn replace_call_spread_if_csi_method_with_member(
     ident_name: &IdentName,
     call: &mut CallExpr,
-    csi_methods: &CsiMethods,
-    member_expr: &MemberExpr,
+    csi_method: &CsiMethod,
+    callee_expr: &Expr,
     call_or_apply: &str,
     ident_provider: &mut dyn IdentProvider,
 ) -> Option<ResultExpr> {
     let method_name = &ident_name.sym.to_string();
 
-    if let Some(csi_method) = csi_methods.get(method_name) {
-        let mut assignations = Vec::new();
-        let mut arguments = Vec::new();
-        let span = call.span;
+    let mut assignations = Vec::new();
+    let mut arguments = Vec::new();
+    let span = call.span;
 
-        // __datadog_token_$i2 = member
-        let ident_callee = ident_provider.get_ident_used_in_assignation(
-            &Expr::Member(member_expr.clone()),
-            &mut assignations,
-            &mut arguments,
-            &span,
-            IdentKind::Expr,
-        );
+    // __datadog_token_$i2 = member
+    let ident_callee = ident_provider.get_ident_used_in_assignation(
+        callee_expr,
+        &mut assignations,
+        &mut arguments,
+        &span,
+        IdentKind::Expr,
+    );
 
-        // return if there is no ident_callee
-        ident_callee.as_ref()?;
+    // return if there is no ident_callee
+    ident_callee.as_ref()?;
 
-        let call_replacement = replace_call_callee_and_args(
-            call,
-            Some(Expr::Ident(ident_callee.unwrap())),
+    let call_replacement = replace_call_callee_and_args(
+        call,
+        Some(Expr::Ident(ident_callee.unwrap())),
+        &mut assignations,
+        &mut arguments,
+        Some(call_or_apply),
+        ident_provider,
+    );
+
+    Some(ResultExpr {
+        tag: method_name.clone(),
+        expr: get_hook_expr(
+            csi_method,
+            call_replacement,
+            &arguments,
             &mut assignations,
-            &mut arguments,
-            Some(call_or_apply),
+            &span,
             ident_provider,
-        );
-
-        return Some(ResultExpr {
-            tag: method_name.clone(),
-            expr: get_dd_paren_expr(
-                &Expr::Call(call_replacement),
-                &arguments,
-                &mut assignations,
-                csi_method.dst.as_str(),
-                &span,
-            ),
-        });
-    }
-    None
+        ),
+    })
 }
 
 fn replace_call_callee_and_args(
@@ -458,3 +675,215 @@ fn replace_call_callee_and_args(
 
     call_replacement
 }
+
+fn get_hook_expr(
+    csi_method: &CsiMethod,
+    call_replacement: CallExpr,
+    arguments: &[ExprOrSpread],
+    assignations: &mut Vec<Expr>,
+    span: &Span,
+    ident_provider: &mut dyn IdentProvider,
+) -> Expr {
+    if csi_method.kind == CsiMethodKind::After {
+        return get_dd_paren_expr(
+            &Expr::Call(call_replacement),
+            arguments,
+            assignations,
+            csi_method.dst.as_str(),
+            span,
+        );
+    }
+
+    // the before hook receives the already evaluated callee, receiver and arguments and the
+    // original method is applied to the arguments it returns
+    //  a.substring(b) -> __datadog_token_$i = a, __datadog_token_$i2 = __datadog_token_$i.substring, __datadog_token_$i3 = b,
+    //      __datadog_token_$i4 = _ddiast.substringBefore(__datadog_token_$i2, __datadog_token_$i, [__datadog_token_$i3]),
+    //      __datadog_token_$i2.apply(__datadog_token_$i, __datadog_token_$i4)
+    let (callee, this_arg, args) = split_call_replacement(&call_replacement, span);
+
+    let before_call = get_dd_call_expr(
+        &callee,
+        &[
+            ExprOrSpread::from(this_arg.clone()),
+            ExprOrSpread::from(args),
+        ],
+        csi_method.before_dst().as_str(),
+        span,
+    );
+    let before_args = ident_provider
+        .get_temporal_ident_used_in_assignation(&before_call, assignations, span, IdentKind::Expr)
+        .map_or(before_call, Expr::Ident);
+
+    let apply_call = Expr::Call(CallExpr {
+        span: *span,
+        callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+            span: *span,
+            obj: Box::new(callee.clone()),
+            prop: MemberProp::Ident(IdentName::new("apply".into(), *span)),
+        }))),
+        args: vec![
+            ExprOrSpread::from(this_arg.clone()),
+            ExprOrSpread::from(before_args.clone()),
+        ],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    });
+
+    if csi_method.kind.has_after_hook() {
+        // the after hook receives the arguments returned by the before hook
+        let after_arguments = vec![
+            ExprOrSpread::from(callee),
+            ExprOrSpread::from(this_arg),
+            ExprOrSpread {
+                spread: Some(*span),
+                expr: Box::new(before_args),
+            },
+        ];
+        get_dd_paren_expr(
+            &apply_call,
+            &after_arguments,
+            assignations,
+            csi_method.dst.as_str(),
+            span,
+        )
+    } else {
+        assignations.push(apply_call);
+        Expr::Paren(ParenExpr {
+            span: *span,
+            expr: Box::new(Expr::Seq(SeqExpr {
+                span: *span,
+                exprs: assignations.drain(..).map(Box::new).collect(),
+            })),
+        })
+    }
+}
+
+// callee, receiver and arguments array of a rewritten `fn.call(this, ...args)`,
+// `fn.apply(this, args)` or `aloneMethod(...args)` call
+fn split_call_replacement(call: &CallExpr, span: &Span) -> (Expr, Expr, Expr) {
+    let (callee, call_or_apply) = match &call.callee {
+        Callee::Expr(callee) => match &**callee {
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) if FunctionPrototypeTransform::is_call_or_apply(prop) => {
+                (*obj.clone(), Some(prop.sym.to_string()))
+            }
+            expr => (expr.clone(), None),
+        },
+        _ => (undefined_expr(span), None),
+    };
+
+    let args = &call.args;
+    match call_or_apply.as_deref() {
+        Some("apply") => {
+            // spread arguments are always stored in a temporal array: fn.apply(...__datadog_token_$i)
+            let (this_arg, args_array) = match args.first() {
+                Some(ExprOrSpread {
+                    spread: Some(_),
+                    expr,
+                }) => (array_element(expr, 0, span), array_element(expr, 1, span)),
+                first => (
+                    first.map_or_else(|| undefined_expr(span), |arg| *arg.expr.clone()),
+                    match args.get(1) {
+                        Some(ExprOrSpread {
+                            spread: Some(_),
+                            expr,
+                        }) => array_element(expr, 0, span),
+                        Some(arg) => *arg.expr.clone(),
+                        None => array_expr(vec![], span),
+                    },
+                ),
+            };
+            (callee, this_arg, args_array)
+        }
+        Some(_) => match args.first() {
+            // fn.call(...__datadog_token_$i) -> __datadog_token_$i[0], [...__datadog_token_$i.slice(1)]
+            Some(ExprOrSpread {
+                spread: Some(_),
+                expr,
+            }) => {
+                let mut elems = vec![Some(ExprOrSpread {
+                    spread: Some(*span),
+                    expr: Box::new(array_slice(expr, span)),
+                })];
+                elems.extend(args.iter().skip(1).cloned().map(Some));
+                (
+                    callee,
+                    array_element(expr, 0, span),
+                    array_expr(elems, span),
+                )
+            }
+            first => (
+                callee,
+                first.map_or_else(|| undefined_expr(span), |arg| *arg.expr.clone()),
+                array_expr(args.iter().skip(1).cloned().map(Some).collect(), span),
+            ),
+        },
+        None => (
+            callee,
+            undefined_expr(span),
+            array_expr(args.iter().cloned().map(Some).collect(), span),
+        ),
+    }
+}
+
+// Class.prototype.method
+fn prototype_method_expr(prototype_method: &PrototypeMethod, span: &Span) -> Expr {
+    let prototype = Expr::Member(MemberExpr {
+        span: *span,
+        obj: Box::new(Expr::Ident(Ident::new(
+            prototype_method.class.clone(),
+            *span,
+            SyntaxContext::empty(),
+        ))),
+        prop: MemberProp::Ident(IdentName::new(PROTOTYPE.into(), *span)),
+    });
+    Expr::Member(MemberExpr {
+        span: *span,
+        obj: Box::new(prototype),
+        prop: MemberProp::Ident(IdentName::new(
+            prototype_method.method.clone().into(),
+            *span,
+        )),
+    })
+}
+
+fn undefined_expr(span: &Span) -> Expr {
+    Expr::Ident(Ident {
+        span: *span,
+        sym: JsWord::from("undefined"),
+        optional: false,
+        ctxt: SyntaxContext::empty(),
+    })
+}
+
+fn array_expr(elems: Vec<Option<ExprOrSpread>>, span: &Span) -> Expr {
+    Expr::Array(ArrayLit { span: *span, elems })
+}
+
+fn array_element(array: &Expr, index: usize, span: &Span) -> Expr {
+    Expr::Member(MemberExpr {
+        span: *span,
+        obj: Box::new(array.clone()),
+        prop: MemberProp::Computed(ComputedPropName {
+            span: *span,
+            expr: Box::new(Expr::Lit(Lit::Num(Number::from(index as f64)))),
+        }),
+    })
+}
+
+fn array_slice(array: &Expr, span: &Span) -> Expr {
+    Expr::Call(CallExpr {
+        span: *span,
+        callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+            span: *span,
+            obj: Box::new(array.clone()),
+            prop: MemberProp::Ident(IdentName::new("slice".into(), *span)),
+        }))),
+        args: vec![ExprOrSpread::from(Expr::Lit(Lit::Num(Number::from(1.0))))],
+        type_args: None,
+        ctxt: SyntaxContext::empty(),
+    })
+}
@@ -45,64 +45,76 @@ impl FunctionPrototypeTransform {
 
         let mut path_parts = vec![];
         if get_prototype_member_path(member, &mut path_parts) {
-            if call.args.is_empty() {
-                return None;
-            }
-
-            let method_ident = path_parts[0].clone();
-            let this_expr_or_spread = &call.args[0];
-
-            // ...$this_expr - we can not return an Expr for an spread expression
-            if this_expr_or_spread.spread.is_some() {
-                return Some((this_expr_or_spread.clone(), method_ident, call.clone()));
-            }
-
-            if invalid_args(ident_name, call) {
-                return None;
-            }
-
-            let filtered_args = call
-                .args
-                .iter()
-                .skip(1)
-                .cloned()
-                .collect::<Vec<ExprOrSpread>>();
-
-            let this_expr = &this_expr_or_spread.expr;
-
-            if this_expr.is_lit()
-                && (!csi_methods.method_allows_literal_callers(&method_ident.sym)
-                    || all_args_are_literal(&filtered_args))
-            {
-                return None;
-            }
-
-            let new_callee = MemberExpr {
-                obj: this_expr.clone(),
-                prop: MemberProp::Ident(IdentName::new(
-                    method_ident.sym.clone(),
-                    method_ident.span,
-                )),
-                span: call.span,
-            };
-
-            let new_call = CallExpr {
-                args: filtered_args,
-                callee: Callee::Expr(Box::new(Expr::Member(new_callee))),
-                span: call.span,
-                type_args: None,
-                ctxt: SyntaxContext::empty(),
-            };
-
-            return Some((
-                ExprOrSpread::from(*this_expr.clone()),
-                method_ident,
-                new_call,
-            ));
+            return Self::get_expression_parts_from_method_call_or_apply(
+                call,
+                path_parts[0].clone(),
+                ident_name,
+                csi_methods,
+            );
         }
 
         None
     }
+
+    /// same as `get_expression_parts_from_call_or_apply` for a `$method_name.[call|apply]($this_expr, $arguments)`
+    /// call whose method name is already known, like a prototype method stored in a variable
+    pub fn get_expression_parts_from_method_call_or_apply(
+        call: &CallExpr,
+        method_ident: Ident,
+        ident_name: &IdentName,
+        csi_methods: &CsiMethods,
+    ) -> Option<(ExprOrSpread, Ident, CallExpr)> {
+        if call.args.is_empty() {
+            return None;
+        }
+
+        let this_expr_or_spread = &call.args[0];
+
+        // ...$this_expr - we can not return an Expr for an spread expression
+        if this_expr_or_spread.spread.is_some() {
+            return Some((this_expr_or_spread.clone(), method_ident, call.clone()));
+        }
+
+        if invalid_args(ident_name, call) {
+            return None;
+        }
+
+        let filtered_args = call
+            .args
+            .iter()
+            .skip(1)
+            .cloned()
+            .collect::<Vec<ExprOrSpread>>();
+
+        let this_expr = &this_expr_or_spread.expr;
+
+        if this_expr.is_lit()
+            && (!csi_methods.method_allows_literal_callers(&method_ident.sym)
+                || all_args_are_literal(&filtered_args))
+        {
+            return None;
+        }
+
+        let new_callee = MemberExpr {
+            obj: this_expr.clone(),
+            prop: MemberProp::Ident(IdentName::new(method_ident.sym.clone(), method_ident.span)),
+            span: call.span,
+        };
+
+        let new_call = CallExpr {
+            args: filtered_args,
+            callee: Callee::Expr(Box::new(Expr::Member(new_callee))),
+            span: call.span,
+            type_args: None,
+            ctxt: SyntaxContext::empty(),
+        };
+
+        Some((
+            ExprOrSpread::from(*this_expr.clone()),
+            method_ident,
+            new_call,
+        ))
+    }
 }
 
 fn get_prototype_member_path(member: &MemberExpr, parts: &mut Vec<Ident>) -> bool {
@@ -0,0 +1,108 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use swc_ecma_ast::*;
+
+use crate::visitor::{
+    csi_methods::CsiMethods,
+    ident_provider::{IdentKind, IdentProvider},
+    visitor_util::get_dd_paren_expr,
+};
+
+use super::{call_expr_transform::get_static_prop_name, transform_status::TransformResult};
+
+pub struct MemberExprTransform {}
+
+impl MemberExprTransform {
+    pub fn to_dd_member_expr(
+        member: &MemberExpr,
+        csi_methods: &CsiMethods,
+        ident_provider: &mut dyn IdentProvider,
+    ) -> TransformResult<Expr> {
+        let csi_method = get_receiver_path(&member.obj)
+            .and_then(|receiver_path| csi_methods.get_property_read(&receiver_path));
+
+        let Some(csi_method) = csi_method else {
+            return TransformResult::not_modified();
+        };
+
+        let mut assignations = Vec::new();
+        let mut arguments = Vec::new();
+        let span = member.span;
+
+        // req.query.a -> (__datadog_token_$i = req.query,
+        //      _ddiast.queryRead(__datadog_token_$i.a, __datadog_token_$i, 'a'))
+        let obj = match &*member.obj {
+            Expr::Ident(_) | Expr::This(_) => {
+                arguments.push(ExprOrSpread::from(*member.obj.clone()));
+                *member.obj.clone()
+            }
+            obj => ident_provider
+                .get_ident_used_in_assignation(
+                    obj,
+                    &mut assignations,
+                    &mut arguments,
+                    &span,
+                    IdentKind::Expr,
+                )
+                .map_or_else(|| obj.clone(), Expr::Ident),
+        };
+
+        // req.headers[h] -> (__datadog_token_$i = req.headers, __datadog_token_$i2 = h,
+        //      _ddiast.headersRead(__datadog_token_$i[__datadog_token_$i2], __datadog_token_$i, __datadog_token_$i2))
+        let prop = match &member.prop {
+            MemberProp::Ident(ident) => {
+                arguments.push(ExprOrSpread::from(Expr::Lit(Lit::Str(
+                    ident.sym.clone().into(),
+                ))));
+                member.prop.clone()
+            }
+            MemberProp::Computed(computed) => {
+                let key = ident_provider
+                    .get_ident_used_in_assignation(
+                        &computed.expr,
+                        &mut assignations,
+                        &mut arguments,
+                        &span,
+                        IdentKind::Expr,
+                    )
+                    .map_or_else(|| *computed.expr.clone(), Expr::Ident);
+                MemberProp::Computed(ComputedPropName {
+                    span: computed.span,
+                    expr: Box::new(key),
+                })
+            }
+            MemberProp::PrivateName(_) => return TransformResult::not_modified(),
+        };
+
+        let member_replacement = MemberExpr {
+            span,
+            obj: Box::new(obj),
+            prop,
+        };
+
+        let dd_expr = get_dd_paren_expr(
+            &Expr::Member(member_replacement),
+            &arguments,
+            &mut assignations,
+            csi_method.dst.as_str(),
+            &span,
+        );
+
+        TransformResult::modified_with_tag(dd_expr, csi_method.src.clone())
+    }
+}
+
+// dotted path of an identifier or `this` followed by static properties: req.query or req['query']
+fn get_receiver_path(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::This(_) => Some("this".to_string()),
+        Expr::Member(MemberExpr { obj, prop, .. }) => {
+            let prop_name = get_static_prop_name(prop)?;
+            get_receiver_path(obj).map(|path| format!("{path}.{}", prop_name.sym))
+        }
+        _ => None,
+    }
+}
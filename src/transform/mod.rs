@@ -7,7 +7,11 @@ pub(crate) mod assign_add_transform;
 pub(crate) mod binary_add_transform;
 pub(crate) mod call_expr_transform;
 pub(crate) mod function_prototype_transform;
+pub(crate) mod member_expr_transform;
+pub(crate) mod new_expr_transform;
 pub(crate) mod operand_handler;
 pub(crate) mod opt_chain_transform;
+pub(crate) mod property_write_transform;
+pub(crate) mod tagged_template_transform;
 pub(crate) mod template_transform;
 pub(crate) mod transform_status;
@@ -0,0 +1,107 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use swc_ecma_ast::*;
+
+use crate::visitor::{
+    csi_methods::CsiMethods,
+    ident_provider::{IdentKind, IdentProvider},
+    symbol_table::{ModuleRef, SymbolTable},
+    visitor_util::get_dd_paren_expr,
+};
+
+use super::{
+    operand_handler::{DefaultOperandHandler, ExpandArrays, IdentMode, OperandHandler},
+    transform_status::TransformResult,
+};
+
+pub struct NewExprTransform {}
+
+impl NewExprTransform {
+    pub fn to_dd_new_expr(
+        new: &NewExpr,
+        csi_methods: &CsiMethods,
+        symbol_table: &SymbolTable,
+        ident_provider: &mut dyn IdentProvider,
+    ) -> TransformResult<Expr> {
+        let csi_method = match &*new.callee {
+            // new URL(a) or new URL(a) with URL bound to require('url').URL
+            Expr::Ident(ident) => {
+                let module_ref = symbol_table.resolve(&new.callee);
+                module_ref
+                    .as_ref()
+                    .and_then(ModuleRef::export)
+                    .and_then(|(module, export)| csi_methods.get_constructor(export, Some(module)))
+                    .filter(|csi_method| csi_method.receiver.is_some())
+                    .or_else(|| csi_methods.get_constructor(&ident.sym, None))
+            }
+
+            // new url.URL(a)
+            Expr::Member(MemberExpr {
+                obj,
+                prop: MemberProp::Ident(prop),
+                ..
+            }) => {
+                let module_ref = symbol_table.resolve(obj);
+                let receiver = module_ref.as_ref().and_then(ModuleRef::namespace);
+                csi_methods.get_constructor(&prop.sym, receiver)
+            }
+            _ => None,
+        };
+
+        let Some(csi_method) = csi_method else {
+            return TransformResult::not_modified();
+        };
+
+        let mut assignations = Vec::new();
+        let mut arguments = Vec::new();
+        let span = new.span;
+
+        // new a.B(c) -> (__datadog_token_$i = a.B, __datadog_token_$i2 = c,
+        //      _ddiast.B(new __datadog_token_$i(__datadog_token_$i2), __datadog_token_$i, __datadog_token_$i2))
+        let callee = match &*new.callee {
+            Expr::Ident(_) => {
+                arguments.push(ExprOrSpread::from(*new.callee.clone()));
+                *new.callee.clone()
+            }
+            callee => ident_provider
+                .get_ident_used_in_assignation(
+                    callee,
+                    &mut assignations,
+                    &mut arguments,
+                    &span,
+                    IdentKind::Expr,
+                )
+                .map_or_else(|| callee.clone(), Expr::Ident),
+        };
+
+        let mut new_replacement = new.clone();
+        new_replacement.callee = Box::new(callee);
+        new_replacement
+            .args
+            .iter_mut()
+            .flatten()
+            .for_each(|expr_or_spread| {
+                DefaultOperandHandler::replace_expressions_in_expr_or_spread(
+                    expr_or_spread,
+                    IdentMode::Replace,
+                    &mut assignations,
+                    &mut arguments,
+                    &span,
+                    ident_provider,
+                    ExpandArrays::No,
+                )
+            });
+
+        let dd_expr = get_dd_paren_expr(
+            &Expr::New(new_replacement),
+            &arguments,
+            &mut assignations,
+            csi_method.dst.as_str(),
+            &span,
+        );
+
+        TransformResult::modified_with_tag(dd_expr, csi_method.src.clone())
+    }
+}
@@ -0,0 +1,173 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use swc_ecma_ast::*;
+
+use crate::visitor::{
+    csi_methods::CsiMethods,
+    ident_provider::{IdentKind, IdentProvider},
+    visitor_util::get_dd_paren_expr,
+};
+
+use super::transform_status::TransformResult;
+
+pub struct PropertyWriteTransform {}
+
+impl PropertyWriteTransform {
+    pub fn to_dd_assign_expr(
+        assign: &AssignExpr,
+        csi_methods: &CsiMethods,
+        ident_provider: &mut dyn IdentProvider,
+    ) -> TransformResult<Expr> {
+        // obj[key] ??= value only assigns when obj[key] is nullish
+        let short_circuit_op = match assign.op {
+            AssignOp::Assign => None,
+            AssignOp::AndAssign => Some(BinaryOp::LogicalAnd),
+            AssignOp::OrAssign => Some(BinaryOp::LogicalOr),
+            AssignOp::NullishAssign => Some(BinaryOp::NullishCoalescing),
+            _ => return TransformResult::not_modified(),
+        };
+
+        // only computed keys not known until runtime: obj[key] = value
+        let (member, computed) = match &assign.left {
+            AssignTarget::Simple(SimpleAssignTarget::Member(
+                member @ MemberExpr {
+                    prop: MemberProp::Computed(computed),
+                    ..
+                },
+            )) if !computed.expr.is_lit() => (member, computed),
+            _ => return TransformResult::not_modified(),
+        };
+
+        let mut assignations = Vec::new();
+        let mut arguments = Vec::new();
+        let span = assign.span;
+
+        // obj[key] = value -> (__datadog_token_$i = obj, __datadog_token_$i2 = key, __datadog_token_$i3 = value,
+        //      _ddiast.propertyWriteOperator(__datadog_token_$i[__datadog_token_$i2] = __datadog_token_$i3,
+        //          __datadog_token_$i, __datadog_token_$i2, __datadog_token_$i3))
+        //
+        // obj is stored even when it is an identifier because the key or the value could reassign it
+        let obj = match &*member.obj {
+            Expr::This(_) => {
+                arguments.push(ExprOrSpread::from(*member.obj.clone()));
+                *member.obj.clone()
+            }
+            obj => ident_provider
+                .get_ident_used_in_assignation(
+                    obj,
+                    &mut assignations,
+                    &mut arguments,
+                    &span,
+                    IdentKind::Expr,
+                )
+                .map_or_else(|| obj.clone(), Expr::Ident),
+        };
+
+        let key = ident_provider
+            .get_ident_used_in_assignation(
+                &computed.expr,
+                &mut assignations,
+                &mut arguments,
+                &span,
+                IdentKind::Expr,
+            )
+            .map_or_else(|| *computed.expr.clone(), Expr::Ident);
+
+        let member_replacement = MemberExpr {
+            span: member.span,
+            obj: Box::new(obj),
+            prop: MemberProp::Computed(ComputedPropName {
+                span: computed.span,
+                expr: Box::new(key),
+            }),
+        };
+
+        // an anonymous function stored in a temporal var would be named after it
+        let right = if is_anonymous_function(&assign.right) {
+            Expr::Paren(ParenExpr {
+                span,
+                expr: Box::new(Expr::Seq(SeqExpr {
+                    span,
+                    exprs: vec![
+                        Box::new(Expr::Lit(Lit::Num(Number::from(0.0)))),
+                        assign.right.clone(),
+                    ],
+                })),
+            })
+        } else {
+            *assign.right.clone()
+        };
+
+        // the value of a logical assignment is evaluated and hooked only when it is assigned
+        let mut value_assignations = Vec::new();
+        let value = ident_provider
+            .get_ident_used_in_assignation(
+                &right,
+                match short_circuit_op {
+                    Some(_) => &mut value_assignations,
+                    None => &mut assignations,
+                },
+                &mut arguments,
+                &span,
+                IdentKind::Expr,
+            )
+            .map_or_else(|| right.clone(), Expr::Ident);
+
+        let assign_replacement = AssignExpr {
+            span,
+            op: AssignOp::Assign,
+            left: AssignTarget::Simple(SimpleAssignTarget::Member(member_replacement.clone())),
+            right: Box::new(value),
+        };
+
+        let Some(short_circuit_op) = short_circuit_op else {
+            let dd_expr = get_dd_paren_expr(
+                &Expr::Assign(assign_replacement),
+                &arguments,
+                &mut assignations,
+                &csi_methods.get_dd_property_write_operator_name(),
+                &span,
+            );
+            return TransformResult::modified(dd_expr);
+        };
+
+        // obj[key] ??= value -> (__datadog_token_$i = obj, __datadog_token_$i2 = key,
+        //      __datadog_token_$i[__datadog_token_$i2] ?? (__datadog_token_$i3 = value,
+        //          _ddiast.propertyWriteOperator(__datadog_token_$i[__datadog_token_$i2] = __datadog_token_$i3, ...)))
+        let dd_assign_expr = get_dd_paren_expr(
+            &Expr::Assign(assign_replacement),
+            &arguments,
+            &mut value_assignations,
+            &csi_methods.get_dd_property_write_operator_name(),
+            &span,
+        );
+
+        assignations.push(Expr::Bin(BinExpr {
+            span,
+            op: short_circuit_op,
+            left: Box::new(Expr::Member(member_replacement)),
+            right: Box::new(dd_assign_expr),
+        }));
+
+        TransformResult::modified(Expr::Paren(ParenExpr {
+            span,
+            expr: Box::new(Expr::Seq(SeqExpr {
+                span,
+                exprs: assignations.into_iter().map(Box::new).collect(),
+            })),
+        }))
+    }
+}
+
+// function, arrow or class expression taking its name from the binding it is assigned to
+fn is_anonymous_function(expr: &Expr) -> bool {
+    match expr {
+        Expr::Fn(FnExpr { ident: None, .. })
+        | Expr::Arrow(_)
+        | Expr::Class(ClassExpr { ident: None, .. }) => true,
+        Expr::Paren(paren) => is_anonymous_function(&paren.expr),
+        _ => false,
+    }
+}
@@ -0,0 +1,156 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use swc_common::{Span, SyntaxContext, DUMMY_SP};
+use swc_ecma_ast::*;
+
+use crate::visitor::{
+    csi_methods::CsiMethods,
+    ident_provider::{IdentKind, IdentProvider},
+    visitor_util::get_dd_paren_expr,
+};
+
+use super::transform_status::TransformResult;
+
+const STRINGS_PARAM: &str = "strings";
+const CALL: &str = "call";
+
+pub struct TaggedTemplateTransform {}
+
+impl TaggedTemplateTransform {
+    pub fn to_dd_tagged_tpl_expr(
+        tagged_tpl: &TaggedTpl,
+        csi_methods: &CsiMethods,
+        ident_provider: &mut dyn IdentProvider,
+    ) -> TransformResult<Expr> {
+        if tagged_tpl.tpl.exprs.is_empty() {
+            return TransformResult::not_modified();
+        }
+
+        let mut assignations = Vec::new();
+        let span = tagged_tpl.span;
+
+        // a.b`${c}` is invoked with a as this
+        let (tag, this) = match &*tagged_tpl.tag {
+            Expr::Ident(_) => (*tagged_tpl.tag.clone(), None),
+            Expr::Member(member) => {
+                let obj = store(&member.obj, &mut assignations, &span, ident_provider);
+                let tag = Expr::Member(MemberExpr {
+                    span: member.span,
+                    obj: Box::new(obj.clone()),
+                    prop: member.prop.clone(),
+                });
+                (
+                    store(&tag, &mut assignations, &span, ident_provider),
+                    Some(obj),
+                )
+            }
+            Expr::SuperProp(_) => return TransformResult::not_modified(),
+            tag => (store(tag, &mut assignations, &span, ident_provider), None),
+        };
+
+        let substitutions = tagged_tpl
+            .tpl
+            .exprs
+            .iter()
+            .map(|tpl_expr| Box::new(store(tpl_expr, &mut assignations, &span, ident_provider)))
+            .collect::<Vec<_>>();
+
+        // the template strings object is obtained from a single tagged template, so every evaluation
+        // of the rewritten site passes the same frozen object to the tag
+        let strings_tpl = Expr::TaggedTpl(TaggedTpl {
+            span,
+            ctxt: SyntaxContext::empty(),
+            tag: Box::new(Expr::Paren(ParenExpr {
+                span,
+                expr: Box::new(get_identity_arrow()),
+            })),
+            type_params: None,
+            tpl: Box::new(Tpl {
+                span: tagged_tpl.tpl.span,
+                exprs: substitutions.clone(),
+                quasis: tagged_tpl.tpl.quasis.clone(),
+            }),
+        });
+        let strings = store(&strings_tpl, &mut assignations, &span, ident_provider);
+
+        let mut call_args = vec![ExprOrSpread::from(strings.clone())];
+        call_args.extend(
+            substitutions
+                .iter()
+                .map(|substitution| ExprOrSpread::from(*substitution.clone())),
+        );
+
+        // tag`a${b}` -> (__datadog_token_$i = b, __datadog_token_$i2 = ((strings) => strings)`a${__datadog_token_$i}`,
+        //      _ddiast.taggedTplOperator(tag(__datadog_token_$i2, __datadog_token_$i), tag, __datadog_token_$i2, __datadog_token_$i))
+        let call = match this {
+            Some(this) => {
+                let mut args = vec![ExprOrSpread::from(this)];
+                args.extend(call_args);
+                CallExpr {
+                    span,
+                    ctxt: SyntaxContext::empty(),
+                    callee: Callee::Expr(Box::new(Expr::Member(MemberExpr {
+                        span,
+                        obj: Box::new(tag.clone()),
+                        prop: MemberProp::Ident(IdentName::new(CALL.into(), span)),
+                    }))),
+                    args,
+                    type_args: None,
+                }
+            }
+            None => CallExpr {
+                span,
+                ctxt: SyntaxContext::empty(),
+                callee: Callee::Expr(Box::new(tag.clone())),
+                args: call_args,
+                type_args: tagged_tpl.type_params.clone(),
+            },
+        };
+
+        let mut arguments = vec![ExprOrSpread::from(tag), ExprOrSpread::from(strings)];
+        arguments.extend(
+            substitutions
+                .into_iter()
+                .map(|substitution| ExprOrSpread::from(*substitution)),
+        );
+
+        let dd_expr = get_dd_paren_expr(
+            &Expr::Call(call),
+            &arguments,
+            &mut assignations,
+            &csi_methods.get_dd_tagged_tpl_operator_name(),
+            &span,
+        );
+
+        TransformResult::modified(dd_expr)
+    }
+}
+
+// the temporal var the expression is assigned to or the expression itself when it is a literal
+fn store(
+    expr: &Expr,
+    assignations: &mut Vec<Expr>,
+    span: &Span,
+    ident_provider: &mut dyn IdentProvider,
+) -> Expr {
+    ident_provider
+        .get_temporal_ident_used_in_assignation(expr, assignations, span, IdentKind::Expr)
+        .map_or_else(|| expr.clone(), Expr::Ident)
+}
+
+// (strings) => strings
+fn get_identity_arrow() -> Expr {
+    let strings = Ident::new(STRINGS_PARAM.into(), DUMMY_SP, SyntaxContext::empty());
+    Expr::Arrow(ArrowExpr {
+        span: DUMMY_SP,
+        ctxt: SyntaxContext::empty(),
+        params: vec![Pat::Ident(BindingIdent::from(strings.clone()))],
+        body: Box::new(BlockStmtOrExpr::Expr(Box::new(Expr::Ident(strings)))),
+        is_async: false,
+        is_generator: false,
+        type_params: None,
+        return_type: None,
+    })
+}
@@ -4,13 +4,14 @@
  **/
 use std::fmt::{self, Debug, Display};
 
+use swc_common::Span;
+
 use crate::{rewriter::Config, telemetry::IastTelemetry};
 
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub enum Status {
     Modified,
     NotModified,
-    Cancelled,
 }
 
 impl Display for Status {
@@ -19,19 +20,39 @@ impl Display for Status {
     }
 }
 
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SiteKind {
+    Operator,
+    Template,
+    TaggedTemplate,
+    PropertyWrite,
+    Method,
+    Constructor,
+    PropertyRead,
+}
+
+// rewritten site whose id is embedded in the generated hook call
+#[derive(Clone, Debug)]
+pub struct Site {
+    pub id: usize,
+    pub kind: SiteKind,
+    pub tag: String,
+    pub span: Span,
+}
+
 #[derive(Debug)]
 pub struct TransformStatus {
     pub status: Status,
-    pub msg: Option<String>,
     pub telemetry: IastTelemetry,
+    pub sites: Vec<Site>,
 }
 
 impl TransformStatus {
     pub fn not_modified(config: &Config) -> TransformStatus {
         TransformStatus {
             status: Status::NotModified,
-            msg: None,
             telemetry: IastTelemetry::new(config),
+            sites: Vec::new(),
         }
     }
 }
@@ -2,81 +2,173 @@
 * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
 * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
 **/
-use super::{ident_provider::DefaultIdentProvider, visitor_with_context::Ctx};
+use super::{
+    ident_provider::{DefaultIdentProvider, ReservedIdents},
+    symbol_table::SymbolTable,
+    visitor_with_context::Ctx,
+};
 use crate::{
     rewriter::Config,
     transform::transform_status::{Status, TransformStatus},
-    visitor::{
-        operation_transform_visitor::OperationTransformVisitor,
-        visitor_util::get_dd_local_variable_prefix,
-    },
+    visitor::operation_transform_visitor::OperationTransformVisitor,
 };
-use std::collections::HashSet;
-use swc_common::{SyntaxContext, DUMMY_SP};
+use swc_common::{util::take::Take, Span, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::{Stmt::Decl as DeclEnumOption, *};
 use swc_ecma_visit::{Visit, VisitMut, VisitMutWith};
 
 pub struct BlockTransformVisitor<'a> {
     pub transform_status: &'a mut TransformStatus,
     pub config: &'a Config,
+    reserved_idents: ReservedIdents,
+    symbol_table: &'a SymbolTable,
+    scopes: Vec<BlockScope>,
+}
+
+// user identifiers looking like temporal vars within an enclosing block or program
+struct BlockScope {
+    reserved_idents: ReservedIdents,
 }
 
 impl BlockTransformVisitor<'_> {
     pub fn default<'a>(
         transform_status: &'a mut TransformStatus,
         config: &'a Config,
+        symbol_table: &'a SymbolTable,
     ) -> BlockTransformVisitor<'a> {
         BlockTransformVisitor {
             transform_status,
             config,
+            reserved_idents: ReservedIdents::default(),
+            symbol_table,
+            scopes: Vec::new(),
         }
     }
-
-    fn visit_is_cancelled(&mut self) -> bool {
-        self.transform_status.status == Status::Cancelled
-    }
-
-    fn cancel_visit(&mut self, reason: &str) {
-        self.transform_status.status = Status::Cancelled;
-        self.transform_status.msg = Some(reason.to_string());
-    }
 }
 
 //  Block:
 //  - Find items to instrument (+ or template literals in statements or in while, if... test part)
 //  - Replace found items by (__dd_XXX_1=....)
 //  - Create necessary temporal vars in top of block
+//
+//  Function parameters and class field initializers:
+//  - They can not declare variables so every expression is transformed with its own temporal vars
+//  - Those vars are the parameters of an arrow invoked in place, so every invocation of the
+//    function or every new instance gets its own ones: ((__dd_XXX_1) => (__dd_XXX_1=....))()
+//
+//  Temporal var names used by the original code inside the block are skipped
 
 impl Visit for BlockTransformVisitor<'_> {}
 
 impl VisitMut for BlockTransformVisitor<'_> {
     fn visit_mut_block_stmt(&mut self, expr: &mut BlockStmt) {
-        if self.visit_is_cancelled() {
-            return;
+        self.transform_block(expr, expr.span);
+    }
+
+    fn visit_mut_script(&mut self, script: &mut Script) {
+        // top level statements share the script scope (the CommonJS wrapper function in node)
+        let idents = self.transform_scope(script, script.span);
+        if !idents.is_empty() {
+            let index = get_variable_insertion_index(&script.body);
+            script.body.insert(
+                index,
+                get_variable_declaration(&idents, VarDeclKind::Var, DUMMY_SP),
+            );
         }
-        let mut ident_provider = DefaultIdentProvider::new(&self.config.local_var_prefix);
-        let mut operation_visitor = OperationTransformVisitor {
-            ident_provider: &mut ident_provider,
-            csi_methods: &self.config.csi_methods,
-            transform_status: self.transform_status,
-            ctx: Ctx::root(),
-        };
+    }
 
-        expr.visit_mut_children_with(&mut operation_visitor);
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        let idents = self.transform_scope(module, module.span);
+        if !idents.is_empty() {
+            let index = get_module_variable_insertion_index(&module.body);
+            // var avoids TDZ errors when a circular import runs module code before the declaration
+            module.body.insert(
+                index,
+                ModuleItem::Stmt(get_variable_declaration(
+                    &idents,
+                    VarDeclKind::Var,
+                    DUMMY_SP,
+                )),
+            );
+        }
+    }
 
-        if variables_contains_possible_duplicate(
-            &ident_provider.variable_decl,
-            &self.config.local_var_prefix,
-        ) {
-            return self.cancel_visit("Variable name duplicated");
-        } else {
-            insert_variable_declaration(&ident_provider.idents, expr);
+    // function bodies, catch clauses... share the scope with their parameters
+    // so the whole node span is used to search for temporal var names in the original code
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        function
+            .params
+            .visit_mut_with(&mut EnclosedExprVisitor(self));
+        function.decorators.visit_mut_with(self);
+        function.params.visit_mut_with(self);
+        if let Some(body) = &mut function.body {
+            self.transform_block(body, function.span);
         }
+    }
 
-        expr.visit_mut_children_with(self);
+    fn visit_mut_arrow_expr(&mut self, arrow: &mut ArrowExpr) {
+        arrow.params.visit_mut_with(&mut EnclosedExprVisitor(self));
+        arrow.params.visit_mut_with(self);
+        match &mut *arrow.body {
+            BlockStmtOrExpr::BlockStmt(body) => self.transform_block(body, arrow.span),
+            body => body.visit_mut_with(self),
+        }
+    }
+
+    fn visit_mut_constructor(&mut self, constructor: &mut Constructor) {
+        constructor
+            .params
+            .visit_mut_with(&mut EnclosedExprVisitor(self));
+        constructor.params.visit_mut_with(self);
+        if let Some(body) = &mut constructor.body {
+            self.transform_block(body, constructor.span);
+        }
+    }
+
+    fn visit_mut_setter_prop(&mut self, setter: &mut SetterProp) {
+        setter.key.visit_mut_with(self);
+        setter.param.visit_mut_with(self);
+        if let Some(body) = &mut setter.body {
+            self.transform_block(body, setter.span);
+        }
+    }
+
+    fn visit_mut_catch_clause(&mut self, catch: &mut CatchClause) {
+        catch.param.visit_mut_with(self);
+        self.transform_block(&mut catch.body, catch.span);
+    }
+
+    fn visit_mut_class_prop(&mut self, prop: &mut ClassProp) {
+        prop.value.visit_mut_with(&mut EnclosedExprVisitor(self));
+        prop.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_private_prop(&mut self, prop: &mut PrivateProp) {
+        prop.value.visit_mut_with(&mut EnclosedExprVisitor(self));
+        prop.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_auto_accessor(&mut self, accessor: &mut AutoAccessor) {
+        accessor
+            .value
+            .visit_mut_with(&mut EnclosedExprVisitor(self));
+        accessor.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_jsx_element(&mut self, element: &mut JSXElement) {
+        if self.config.jsx {
+            element.visit_mut_children_with(self);
+        }
+    }
+
+    fn visit_mut_jsx_fragment(&mut self, fragment: &mut JSXFragment) {
+        if self.config.jsx {
+            fragment.visit_mut_children_with(self);
+        }
     }
 
     fn visit_mut_program(&mut self, node: &mut Program) {
+        self.reserved_idents = ReservedIdents::collect(node, &self.config.local_var_prefix);
+
         node.visit_mut_children_with(self);
 
         if self.transform_status.status == Status::Modified {
@@ -112,39 +204,131 @@ impl VisitMut for BlockTransformVisitor<'_> {
     }
 }
 
-fn variables_contains_possible_duplicate(variable_decl: &HashSet<Ident>, prefix: &String) -> bool {
-    let prefix = get_dd_local_variable_prefix(prefix);
-    variable_decl
+impl BlockTransformVisitor<'_> {
+    fn transform_block(&mut self, block: &mut BlockStmt, span: Span) {
+        let idents = self.transform_scope(block, span);
+        if !idents.is_empty() {
+            let index = get_variable_insertion_index(&block.stmts);
+            block.stmts.insert(
+                index,
+                get_variable_declaration(&idents, VarDeclKind::Let, block.span),
+            );
+        }
+    }
+
+    // transforms the direct children of a block or program and then visits nested nodes
+    // returns every temporal variable to be declared in its scope
+    fn transform_scope<N>(&mut self, node: &mut N, span: Span) -> Vec<Ident>
+    where
+        N: for<'b> VisitMutWith<OperationTransformVisitor<'b>> + for<'b> VisitMutWith<Self>,
+    {
+        let reserved_idents = self
+            .scopes
+            .last()
+            .map_or(&self.reserved_idents, |scope| &scope.reserved_idents)
+            .within(span);
+
+        let ident_provider = self.transform_operations(reserved_idents.clone(), |opv| {
+            node.visit_mut_children_with(opv)
+        });
+
+        self.scopes.push(BlockScope { reserved_idents });
+        node.visit_mut_children_with(self);
+        self.scopes.pop();
+
+        ident_provider.idents
+    }
+
+    // transforms an expression which can not declare its own variables (parameters, class fields...)
+    // and declares its temporal variables as the parameters of an arrow invoked in place
+    fn transform_enclosed_expr(&mut self, expr: &mut Expr) {
+        let Some(reserved_idents) = self
+            .scopes
+            .last()
+            .map(|scope| scope.reserved_idents.clone())
+        else {
+            return;
+        };
+
+        let ident_provider =
+            self.transform_operations(reserved_idents, |opv| expr.visit_mut_with(opv));
+        if !ident_provider.idents.is_empty() {
+            expr.map_with_mut(|expr| get_invoked_arrow(&ident_provider.idents, expr));
+        }
+    }
+
+    fn transform_operations(
+        &mut self,
+        reserved_idents: ReservedIdents,
+        visit: impl FnOnce(&mut OperationTransformVisitor),
+    ) -> DefaultIdentProvider {
+        let mut ident_provider =
+            DefaultIdentProvider::new(&self.config.local_var_prefix, reserved_idents);
+        let mut operation_visitor = OperationTransformVisitor {
+            ident_provider: &mut ident_provider,
+            csi_methods: &self.config.csi_methods,
+            symbol_table: self.symbol_table,
+            transform_status: self.transform_status,
+            jsx: self.config.jsx,
+            site_ids: self.config.site_ids,
+            ctx: Ctx::root(),
+        };
+
+        visit(&mut operation_visitor);
+
+        ident_provider
+    }
+}
+
+fn get_variable_declaration(ident_expressions: &[Ident], kind: VarDeclKind, span: Span) -> Stmt {
+    let decls = ident_expressions
         .iter()
-        .any(|var| var.span != DUMMY_SP && var.sym.starts_with(&prefix))
+        .map(|ident| VarDeclarator {
+            span,
+            definite: false,
+            name: Pat::Ident(BindingIdent {
+                id: ident.clone(),
+                type_ann: None,
+            }),
+            init: None,
+        })
+        .collect();
+
+    DeclEnumOption(Decl::Var(Box::new(VarDecl {
+        span,
+        decls,
+        declare: false,
+        kind,
+        ctxt: SyntaxContext::empty(),
+    })))
 }
 
-fn insert_variable_declaration(ident_expressions: &[Ident], expr: &mut BlockStmt) {
-    if !ident_expressions.is_empty() {
-        let span = expr.span;
-        let mut vec = Vec::new();
-        ident_expressions.iter().for_each(|ident| {
-            vec.push(VarDeclarator {
-                span,
-                definite: false,
-                name: Pat::Ident(BindingIdent {
+// ((__dd_XXX_1, __dd_XXX_2) => expr)()
+fn get_invoked_arrow(idents: &[Ident], expr: Expr) -> Expr {
+    let arrow = ArrowExpr {
+        span: DUMMY_SP,
+        params: idents
+            .iter()
+            .map(|ident| {
+                Pat::Ident(BindingIdent {
                     id: ident.clone(),
                     type_ann: None,
-                }),
-                init: None,
-            });
-        });
-        let declaration = DeclEnumOption(Decl::Var(Box::new(VarDecl {
-            span,
-            decls: vec,
-            declare: false,
-            kind: VarDeclKind::Let,
-            ctxt: SyntaxContext::empty(),
-        })));
+                })
+            })
+            .collect(),
+        body: Box::new(BlockStmtOrExpr::Expr(Box::new(expr))),
+        ..Default::default()
+    };
 
-        let index = get_variable_insertion_index(&expr.stmts);
-        expr.stmts.insert(index, declaration);
-    }
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr {
+            span: DUMMY_SP,
+            expr: Box::new(Expr::Arrow(arrow)),
+        }))),
+        args: Vec::new(),
+        ..Default::default()
+    })
 }
 
 fn get_variable_insertion_index(stmts: &[Stmt]) -> usize {
@@ -154,3 +338,24 @@ fn get_variable_insertion_index(stmts: &[Stmt]) -> usize {
         0
     }
 }
+
+fn get_module_variable_insertion_index(items: &[ModuleItem]) -> usize {
+    // keep 'use strict' and leading imports on top
+    items
+        .iter()
+        .position(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(_)) => false,
+            ModuleItem::Stmt(stmt) => !stmt.is_use_strict(),
+            _ => true,
+        })
+        .unwrap_or(items.len())
+}
+
+// transforms the outermost expressions of parameters and class field initializers
+struct EnclosedExprVisitor<'a, 'b>(&'a mut BlockTransformVisitor<'b>);
+
+impl VisitMut for EnclosedExprVisitor<'_, '_> {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        self.0.transform_enclosed_expr(expr);
+    }
+}
@@ -2,7 +2,55 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
  **/
-use super::visitor_util::{DD_PLUS_OPERATOR, DD_TEMPLATE_LITERAL_OPERATOR};
+use super::{
+    symbol_table::module_name,
+    visitor_util::{
+        DD_PLUS_OPERATOR, DD_PROPERTY_WRITE_OPERATOR, DD_TAGGED_TEMPLATE_OPERATOR,
+        DD_TEMPLATE_LITERAL_OPERATOR,
+    },
+};
+
+const BEFORE_HOOK_SUFFIX: &str = "Before";
+const CONSTRUCTOR_HOOK_SUFFIX: &str = "Constructor";
+
+/// When the hooks of a csi method are invoked relative to the original call
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum CsiMethodKind {
+    // _ddiast.<dst>(result, fn, this, ...args) after the original call
+    #[default]
+    After,
+    // _ddiast.<dst>Before(fn, this, args) returning the arguments of the original call
+    Before,
+    Around,
+    // _ddiast.<dst>(instance, constructor, ...args) after a `new` expression
+    Constructor,
+    // _ddiast.<dst>(value, obj, key) after reading a property of the receiver pattern
+    PropertyRead,
+}
+
+impl CsiMethodKind {
+    pub const NAMES: [&'static str; 5] =
+        ["after", "before", "around", "constructor", "propertyRead"];
+
+    pub fn try_parse(kind: &str) -> Option<Self> {
+        match kind {
+            "after" => Some(CsiMethodKind::After),
+            "before" => Some(CsiMethodKind::Before),
+            "around" => Some(CsiMethodKind::Around),
+            "constructor" => Some(CsiMethodKind::Constructor),
+            "propertyRead" => Some(CsiMethodKind::PropertyRead),
+            _ => None,
+        }
+    }
+
+    pub fn has_before_hook(&self) -> bool {
+        matches!(self, CsiMethodKind::Before | CsiMethodKind::Around)
+    }
+
+    pub fn has_after_hook(&self) -> bool {
+        *self != CsiMethodKind::Before
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct CsiMethod {
@@ -10,6 +58,11 @@ pub struct CsiMethod {
     pub dst: String,
     pub operator: bool,
     pub allowed_without_callee: bool,
+    pub kind: CsiMethodKind,
+    // module the method is exported from or invoked on, any receiver when None
+    pub receiver: Option<String>,
+    // dotted path of the objects whose properties are read, like req.query
+    pub receiver_pattern: Option<String>,
 }
 
 impl CsiMethod {
@@ -18,14 +71,53 @@ impl CsiMethod {
         dst: Option<String>,
         operator: bool,
         allowed_without_callee: bool,
+        kind: CsiMethodKind,
+        receiver: Option<String>,
     ) -> Self {
-        let dst = dst.unwrap_or_else(|| src.clone());
+        // a constructor may share its src with a method, so it needs its own default hook
+        let dst = dst.unwrap_or_else(|| match kind {
+            CsiMethodKind::Constructor => format!("{src}{CONSTRUCTOR_HOOK_SUFFIX}"),
+            _ => src.clone(),
+        });
         CsiMethod {
             src,
             dst,
             operator,
             allowed_without_callee,
+            kind,
+            receiver: receiver.map(|receiver| module_name(&receiver).to_string()),
+            receiver_pattern: None,
+        }
+    }
+
+    pub fn with_receiver_pattern(mut self, receiver_pattern: Option<String>) -> Self {
+        self.receiver_pattern = receiver_pattern;
+        self
+    }
+
+    // invoked on calls, not on operators, `new` expressions or property reads
+    fn is_method(&self) -> bool {
+        !self.operator
+            && !matches!(
+                self.kind,
+                CsiMethodKind::Constructor | CsiMethodKind::PropertyRead
+            )
+    }
+
+    pub fn before_dst(&self) -> String {
+        format!("{}{BEFORE_HOOK_SUFFIX}", self.dst)
+    }
+
+    /// Names of the `_ddiast` methods invoked for this csi method
+    pub fn hook_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        if self.kind.has_before_hook() {
+            names.push(self.before_dst());
+        }
+        if self.kind.has_after_hook() {
+            names.push(self.dst.clone());
         }
+        names
     }
 }
 
@@ -34,6 +126,8 @@ pub struct CsiMethods {
     pub methods: Vec<CsiMethod>,
     pub plus_operator: Option<CsiMethod>,
     pub tpl_operator: Option<CsiMethod>,
+    pub property_write_operator: Option<CsiMethod>,
+    pub tagged_tpl_operator: Option<CsiMethod>,
     pub method_with_literal_callers: Vec<&'static str>,
 }
 
@@ -47,10 +141,20 @@ impl CsiMethods {
             csi_method.operator && csi_method.src == DD_TEMPLATE_LITERAL_OPERATOR
         });
 
+        let property_write_operator = csi_methods
+            .iter()
+            .find(|csi_method| csi_method.operator && csi_method.src == DD_PROPERTY_WRITE_OPERATOR);
+
+        let tagged_tpl_operator = csi_methods.iter().find(|csi_method| {
+            csi_method.operator && csi_method.src == DD_TAGGED_TEMPLATE_OPERATOR
+        });
+
         CsiMethods {
             methods: csi_methods.to_vec(),
             plus_operator: plus_operator.cloned(),
             tpl_operator: tpl_operator.cloned(),
+            property_write_operator: property_write_operator.cloned(),
+            tagged_tpl_operator: tagged_tpl_operator.cloned(),
             method_with_literal_callers: vec![
                 "concat",
                 "replace",
@@ -67,14 +171,95 @@ impl CsiMethods {
             methods: vec![],
             plus_operator: None,
             tpl_operator: None,
+            property_write_operator: None,
+            tagged_tpl_operator: None,
             method_with_literal_callers: vec![],
         }
     }
 
+    /// Keeps only the methods whose `src` is in `sources`
+    pub fn subset(&self, sources: &[String]) -> Self {
+        let methods = self
+            .methods
+            .iter()
+            .filter(|csi_method| sources.contains(&csi_method.src))
+            .cloned()
+            .collect::<Vec<CsiMethod>>();
+        CsiMethods::new(&methods)
+    }
+
+    /// Names of the `_ddiast` methods invoked for all the csi methods, without duplicates
+    pub fn hook_names(&self) -> Vec<String> {
+        self.methods
+            .iter()
+            .flat_map(|csi_method| csi_method.hook_names())
+            // several csi methods may share a hook, like trimStart and trimEnd
+            .fold(Vec::new(), |mut names, name| {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+                names
+            })
+    }
+
     pub fn get(&self, method_name: &str) -> Option<&CsiMethod> {
+        self.methods.iter().find(|csi_method| {
+            csi_method.is_method() && csi_method.receiver.is_none() && csi_method.src == method_name
+        })
+    }
+
+    /// The method qualified with the `receiver` module
+    pub fn get_qualified(&self, method_name: &str, receiver: &str) -> Option<&CsiMethod> {
+        self.methods.iter().find(|csi_method| {
+            csi_method.is_method()
+                && csi_method.src == method_name
+                && csi_method.receiver.as_deref() == Some(receiver)
+        })
+    }
+
+    /// The method qualified with the `receiver` module if any or the unqualified one
+    pub fn get_with_receiver(
+        &self,
+        method_name: &str,
+        receiver: Option<&str>,
+    ) -> Option<&CsiMethod> {
+        receiver
+            .and_then(|receiver| self.get_qualified(method_name, receiver))
+            .or_else(|| self.get(method_name))
+    }
+
+    /// The constructor qualified with the `receiver` module if any or the unqualified one
+    pub fn get_constructor(&self, name: &str, receiver: Option<&str>) -> Option<&CsiMethod> {
+        let constructors = || {
+            self.methods.iter().filter(|csi_method| {
+                csi_method.kind == CsiMethodKind::Constructor && csi_method.src == name
+            })
+        };
+        receiver
+            .and_then(|receiver| {
+                constructors().find(|csi_method| csi_method.receiver.as_deref() == Some(receiver))
+            })
+            .or_else(|| constructors().find(|csi_method| csi_method.receiver.is_none()))
+    }
+
+    /// The property read hooked for the objects with the `receiver_path`
+    pub fn get_property_read(&self, receiver_path: &str) -> Option<&CsiMethod> {
+        self.methods.iter().find(|csi_method| {
+            csi_method.kind == CsiMethodKind::PropertyRead
+                && csi_method.receiver_pattern.as_deref() == Some(receiver_path)
+        })
+    }
+
+    pub fn property_read_is_enabled(&self) -> bool {
+        self.methods
+            .iter()
+            .any(|csi_method| csi_method.kind == CsiMethodKind::PropertyRead)
+    }
+
+    pub fn has_receivers(&self) -> bool {
         self.methods
             .iter()
-            .find(|csi_method| !csi_method.operator && csi_method.src == method_name)
+            .any(|csi_method| csi_method.receiver.is_some())
     }
 
     pub fn plus_operator_is_enabled(&self) -> bool {
@@ -85,6 +270,14 @@ impl CsiMethods {
         self.tpl_operator.is_some()
     }
 
+    pub fn property_write_operator_is_enabled(&self) -> bool {
+        self.property_write_operator.is_some()
+    }
+
+    pub fn tagged_tpl_operator_is_enabled(&self) -> bool {
+        self.tagged_tpl_operator.is_some()
+    }
+
     pub fn get_dd_plus_operator_name(&self) -> String {
         match &self.plus_operator {
             Some(csi_method) => csi_method.dst.clone(),
@@ -99,6 +292,20 @@ impl CsiMethods {
         }
     }
 
+    pub fn get_dd_property_write_operator_name(&self) -> String {
+        match &self.property_write_operator {
+            Some(csi_method) => csi_method.dst.clone(),
+            _ => DD_PROPERTY_WRITE_OPERATOR.to_string(),
+        }
+    }
+
+    pub fn get_dd_tagged_tpl_operator_name(&self) -> String {
+        match &self.tagged_tpl_operator {
+            Some(csi_method) => csi_method.dst.clone(),
+            _ => DD_TAGGED_TEMPLATE_OPERATOR.to_string(),
+        }
+    }
+
     pub fn method_allows_literal_callers(&self, method_name: &str) -> bool {
         self.method_with_literal_callers.contains(&method_name)
     }
@@ -2,15 +2,15 @@
  * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
  * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
  **/
-use std::collections::HashSet;
 use swc::atoms::JsWord;
-use swc_common::{Span, SyntaxContext, DUMMY_SP};
+use swc_common::{BytePos, Span, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::{
-    ArrayLit, AssignExpr, AssignOp, AssignTarget, BindingIdent, Expr, ExprOrSpread, Ident,
+    ArrayLit, AssignExpr, AssignOp, AssignTarget, BindingIdent, Expr, ExprOrSpread, Ident, Program,
     SimpleAssignTarget,
 };
+use swc_ecma_visit::{Visit, VisitWith};
 
-use super::visitor_util::get_dd_local_variable_name;
+use super::visitor_util::{get_dd_local_variable_name, get_dd_local_variable_prefix};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum IdentKind {
@@ -135,23 +135,21 @@ pub trait IdentProvider {
     fn get_local_var_prefix(&mut self) -> String;
 
     fn reset_counter(&mut self);
-
-    fn register_variable(&mut self, variable: &Ident);
 }
 
 pub struct DefaultIdentProvider {
     pub ident_counter: usize,
     pub idents: Vec<Ident>,
-    pub variable_decl: HashSet<Ident>,
+    pub reserved_idents: ReservedIdents,
     pub local_var_prefix: String,
 }
 
 impl DefaultIdentProvider {
-    pub fn new(local_var_prefix: &str) -> DefaultIdentProvider {
+    pub fn new(local_var_prefix: &str, reserved_idents: ReservedIdents) -> DefaultIdentProvider {
         DefaultIdentProvider {
             ident_counter: 0,
             idents: Vec::new(),
-            variable_decl: HashSet::new(),
+            reserved_idents,
             local_var_prefix: local_var_prefix.to_string(),
         }
     }
@@ -165,8 +163,14 @@ impl IdentProvider for DefaultIdentProvider {
     }
 
     fn next_ident(&mut self) -> usize {
-        let counter = self.ident_counter;
-        self.ident_counter += 1;
+        let mut counter = self.ident_counter;
+        while self
+            .reserved_idents
+            .contains_index(counter, &self.local_var_prefix)
+        {
+            counter += 1;
+        }
+        self.ident_counter = counter + 1;
         counter
     }
 
@@ -174,11 +178,65 @@ impl IdentProvider for DefaultIdentProvider {
         self.ident_counter = 0;
     }
 
-    fn register_variable(&mut self, variable: &Ident) {
-        self.variable_decl.insert(variable.clone());
-    }
-
     fn get_local_var_prefix(&mut self) -> String {
         self.local_var_prefix.clone()
     }
 }
+
+// user identifiers looking like a temporal var (__datadog_<prefix>_N) and their position
+#[derive(Clone, Default)]
+pub struct ReservedIdents {
+    idents: Vec<(JsWord, BytePos)>,
+}
+
+impl ReservedIdents {
+    pub fn collect(program: &Program, local_var_prefix: &String) -> ReservedIdents {
+        let mut visitor = ReservedIdentsVisitor {
+            prefix: get_dd_local_variable_prefix(local_var_prefix),
+            idents: Vec::new(),
+        };
+        program.visit_with(&mut visitor);
+
+        ReservedIdents {
+            idents: visitor.idents,
+        }
+    }
+
+    // a temporal var declared in a scope only collides with identifiers used inside that scope
+    pub fn within(&self, span: Span) -> ReservedIdents {
+        if self.idents.is_empty() || span.is_dummy() {
+            return self.clone();
+        }
+
+        ReservedIdents {
+            idents: self
+                .idents
+                .iter()
+                .filter(|(_, pos)| span.lo <= *pos && *pos < span.hi)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn contains_index(&self, index: usize, local_var_prefix: &String) -> bool {
+        if self.idents.is_empty() {
+            return false;
+        }
+
+        let name = get_dd_local_variable_name(index, local_var_prefix);
+        self.idents.iter().any(|(sym, _)| *sym == name)
+    }
+}
+
+struct ReservedIdentsVisitor {
+    prefix: String,
+    idents: Vec<(JsWord, BytePos)>,
+}
+
+impl Visit for ReservedIdentsVisitor {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if !ident.span.is_dummy() && ident.sym.starts_with(&self.prefix) {
+            self.idents.push((ident.sym.clone(), ident.span.lo));
+        }
+    }
+}
@@ -0,0 +1,149 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+use log::debug;
+use swc::{sourcemap::SourceMap, Compiler};
+use swc_common::Span;
+use swc_ecma_ast::{
+    CallExpr, Callee, Expr, ExprOrSpread, Lit, MemberProp, Number, Program, UnaryExpr, UnaryOp,
+};
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+use super::{csi_methods::CsiMethods, visitor_util::is_dd_global_method_invocation};
+
+/// Inserts a `'<file index>:<line>:<column>'` location token as the first argument of every
+/// generated hook call, so it can not be mistaken for an operand of variadic hooks. Line and
+/// column are 1-based and refer to the original source map when present. The file index points
+/// to the returned list of files.
+pub struct LocationVisitor<'a> {
+    csi_methods: &'a CsiMethods,
+    file: &'a str,
+    original_source_map: Option<&'a SourceMap>,
+    compiler: &'a Compiler,
+    files: Vec<String>,
+}
+
+impl<'a> LocationVisitor<'a> {
+    pub fn new(
+        csi_methods: &'a CsiMethods,
+        file: &'a str,
+        original_source_map: Option<&'a SourceMap>,
+        compiler: &'a Compiler,
+    ) -> Self {
+        LocationVisitor {
+            csi_methods,
+            file,
+            original_source_map,
+            compiler,
+            files: Vec::new(),
+        }
+    }
+
+    fn is_hook_call(&self, call: &CallExpr) -> bool {
+        match &call.callee {
+            Callee::Expr(callee) => match &**callee {
+                Expr::Member(member) if is_dd_global_method_invocation(member) => {
+                    match &member.prop {
+                        MemberProp::Ident(prop) => {
+                            self.csi_methods.methods.iter().any(|csi_method| {
+                                csi_method.hook_names().iter().any(|name| prop.sym == *name)
+                            })
+                        }
+                        _ => false,
+                    }
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn get_location_token(&mut self, span: &Span) -> Expr {
+        if span.is_dummy() {
+            // code generated by the rewriter or the typescript types strip has no location
+            return Expr::Unary(UnaryExpr {
+                span: *span,
+                op: UnaryOp::Void,
+                arg: Box::new(Expr::Lit(Lit::Num(Number::from(0.0)))),
+            });
+        }
+
+        let (file, line, column) =
+            get_original_location(span, self.file, self.original_source_map, self.compiler);
+
+        let file_index = match self.files.iter().position(|f| *f == file) {
+            Some(index) => index,
+            None => {
+                self.files.push(file);
+                self.files.len() - 1
+            }
+        };
+
+        Expr::Lit(Lit::Str(format!("{file_index}:{line}:{column}").into()))
+    }
+}
+
+/// File, 1-based line and 1-based column of the span in the original source when there is a
+/// source map for the file
+pub fn get_original_location(
+    span: &Span,
+    file: &str,
+    original_source_map: Option<&SourceMap>,
+    compiler: &Compiler,
+) -> (String, usize, usize) {
+    let pos = compiler.cm.lookup_char_pos(span.lo);
+    original_source_map
+        .and_then(|source_map| {
+            source_map
+                .lookup_token(pos.line as u32 - 1, pos.col.0 as u32)
+                .and_then(|token| {
+                    token.get_source().map(|source| {
+                        (
+                            source.to_string(),
+                            token.get_src_line() as usize + 1,
+                            token.get_src_col() as usize + 1,
+                        )
+                    })
+                })
+        })
+        .unwrap_or_else(|| (file.to_string(), pos.line, pos.col.0 + 1))
+}
+
+impl VisitMut for LocationVisitor<'_> {
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        call.visit_mut_children_with(self);
+
+        if self.is_hook_call(call) {
+            let token = self.get_location_token(&call.span);
+            call.args.insert(
+                0,
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(token),
+                },
+            );
+        }
+    }
+}
+
+pub fn add_locations(
+    locations_enabled: bool,
+    file: &str,
+    program: &mut Program,
+    csi_methods: &CsiMethods,
+    original_source_map: Option<&SourceMap>,
+    compiler: &Compiler,
+) -> Option<Vec<String>> {
+    if locations_enabled {
+        debug!("Adding call site locations");
+
+        let mut location_visitor =
+            LocationVisitor::new(csi_methods, file, original_source_map, compiler);
+        program.visit_mut_with(&mut location_visitor);
+
+        Some(location_visitor.files)
+    } else {
+        None
+    }
+}
@@ -13,26 +13,37 @@ use crate::{
         assign_add_transform::AssignAddTransform,
         binary_add_transform::BinaryAddTransform,
         call_expr_transform::CallExprTransform,
+        member_expr_transform::MemberExprTransform,
+        new_expr_transform::NewExprTransform,
         opt_chain_transform::OptChainTransform,
+        property_write_transform::PropertyWriteTransform,
+        tagged_template_transform::TaggedTemplateTransform,
         template_transform::TemplateTransform,
-        transform_status::{Status, TransformStatus},
+        transform_status::{Site, SiteKind, Status, TransformStatus},
     },
 };
 
 use super::{
     csi_methods::CsiMethods,
     ident_provider::IdentProvider,
+    symbol_table::SymbolTable,
+    visitor_util::get_dd_hook_call_mut,
     visitor_with_context::{Ctx, VisitorWithContext},
 };
 
 pub const ADD_TAG: &str = "+";
 pub const ADD_ASSING_TAG: &str = "+=";
 pub const TPL_TAG: &str = "Tpl";
+pub const PROPERTY_WRITE_TAG: &str = "[]=";
+pub const TAGGED_TPL_TAG: &str = "TaggedTpl";
 
 pub struct OperationTransformVisitor<'a> {
     pub ident_provider: &'a mut dyn IdentProvider,
     pub csi_methods: &'a CsiMethods,
+    pub symbol_table: &'a SymbolTable,
     pub transform_status: &'a mut TransformStatus,
+    pub jsx: bool,
+    pub site_ids: bool,
     pub ctx: Ctx,
 }
 
@@ -54,10 +65,6 @@ impl VisitorWithContext for OperationTransformVisitor<'_> {
 
 impl OperationTransformVisitor<'_> {
     fn update_status(&mut self, status: Status, tag: Option<String>) {
-        if self.transform_status.status == Status::Cancelled {
-            return;
-        }
-
         if status != Status::NotModified {
             self.transform_status.status = status;
         }
@@ -66,6 +73,46 @@ impl OperationTransformVisitor<'_> {
             self.transform_status.telemetry.inc(tag);
         }
     }
+
+    // passes the site id as first argument of the hook call generated for the rewritten expression
+    fn add_site(&mut self, expr: &mut Expr, kind: SiteKind, tag: &str) {
+        if !self.site_ids {
+            return;
+        }
+
+        if let Some(hook_call) = get_dd_hook_call_mut(expr) {
+            let id = self.transform_status.sites.len();
+            self.transform_status.sites.push(Site {
+                id,
+                kind,
+                tag: tag.to_string(),
+                span: hook_call.span,
+            });
+            hook_call.args.insert(
+                0,
+                ExprOrSpread {
+                    spread: None,
+                    expr: Box::new(Expr::Lit(Lit::Num(Number::from(id as f64)))),
+                },
+            );
+        }
+    }
+
+    // visits the object and the computed key of a member which is written or called but not the
+    // member itself, so the property read is not hooked
+    fn visit_mut_member_children(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Member(member) => member.visit_mut_children_with(self),
+            Expr::Paren(ParenExpr { expr, .. })
+            | Expr::TsAs(TsAsExpr { expr, .. })
+            | Expr::TsSatisfies(TsSatisfiesExpr { expr, .. })
+            | Expr::TsNonNull(TsNonNullExpr { expr, .. })
+            | Expr::TsTypeAssertion(TsTypeAssertion { expr, .. }) => {
+                self.visit_mut_member_children(expr)
+            }
+            _ => expr.visit_mut_with(self),
+        }
+    }
 }
 
 impl Visit for OperationTransformVisitor<'_> {}
@@ -74,6 +121,9 @@ impl VisitMut for OperationTransformVisitor<'_> {
     fn visit_mut_expr(&mut self, expr: &mut Expr) {
         let plus_operator_enabled = self.csi_methods.plus_operator_is_enabled();
         let tpl_operator_enabled = self.csi_methods.tpl_operator_is_enabled();
+        let property_read_enabled = self.csi_methods.property_read_is_enabled();
+        let property_write_enabled = self.csi_methods.property_write_operator_is_enabled();
+        let tagged_tpl_operator_enabled = self.csi_methods.tagged_tpl_operator_is_enabled();
 
         match expr {
             Expr::Bin(binary) if plus_operator_enabled => {
@@ -82,47 +132,96 @@ impl VisitMut for OperationTransformVisitor<'_> {
                 binary.visit_mut_children_with(opv_with_child_ctx);
 
                 if binary.op == BinaryOp::Add {
+                    let mut modified = false;
                     expr.map_with_mut(|bin| {
                         let result = BinaryAddTransform::to_dd_binary_expr(
                             &bin,
                             opv_with_child_ctx.csi_methods,
                             opv_with_child_ctx.ident_provider,
                         );
+                        modified = result.is_modified();
                         opv_with_child_ctx.update_status(result.status, Some(ADD_TAG.to_string()));
                         result.expr.unwrap_or(bin)
                     });
+                    if modified {
+                        opv_with_child_ctx.add_site(expr, SiteKind::Operator, ADD_TAG);
+                    }
                 }
             }
 
-            Expr::Assign(assign) if plus_operator_enabled => {
+            Expr::Assign(assign) if plus_operator_enabled || property_write_enabled => {
                 let opv_with_child_ctx = &mut *self.with_child_ctx();
                 assign.visit_mut_children_with(opv_with_child_ctx);
 
-                if assign.op == AssignOp::AddAssign {
+                if assign.op == AssignOp::AddAssign && plus_operator_enabled {
+                    let mut modified = false;
                     assign.map_with_mut(|mut assign| {
                         let result =
                             AssignAddTransform::to_dd_assign_expr(&mut assign, opv_with_child_ctx);
+                        modified = result.is_modified();
                         opv_with_child_ctx
                             .update_status(result.status, Some(ADD_ASSING_TAG.to_string()));
                         result.expr.unwrap_or(assign)
                     });
+                    if modified {
+                        opv_with_child_ctx.add_site(expr, SiteKind::Operator, ADD_ASSING_TAG);
+                    }
+                } else if property_write_enabled {
+                    let result = PropertyWriteTransform::to_dd_assign_expr(
+                        assign,
+                        opv_with_child_ctx.csi_methods,
+                        opv_with_child_ctx.ident_provider,
+                    );
+                    if result.is_modified() {
+                        expr.map_with_mut(|e| result.expr.unwrap_or(e));
+                        opv_with_child_ctx
+                            .update_status(result.status, Some(PROPERTY_WRITE_TAG.to_string()));
+                        opv_with_child_ctx.add_site(
+                            expr,
+                            SiteKind::PropertyWrite,
+                            PROPERTY_WRITE_TAG,
+                        );
+                    }
                 }
             }
 
             Expr::Tpl(tpl) if tpl_operator_enabled => {
-                if !tpl.exprs.is_empty() && tpl.exprs.iter().all(|tpl_expr| !tpl_expr.is_lit()) {
+                // literal substitutions are passed through, at least one must be able to propagate
+                if tpl.exprs.iter().any(|tpl_expr| !tpl_expr.is_lit()) {
                     let opv_with_child_ctx = &mut *self.with_child_ctx();
                     tpl.visit_mut_children_with(opv_with_child_ctx);
 
+                    let mut modified = false;
                     expr.map_with_mut(|mut tpl| {
                         let result = TemplateTransform::to_dd_tpl_expr(
                             &mut tpl,
                             opv_with_child_ctx.csi_methods,
                             opv_with_child_ctx.ident_provider,
                         );
+                        modified = result.is_modified();
                         opv_with_child_ctx.update_status(result.status, Some(TPL_TAG.to_string()));
                         result.expr.unwrap_or(tpl)
                     });
+                    if modified {
+                        opv_with_child_ctx.add_site(expr, SiteKind::Template, TPL_TAG);
+                    }
+                }
+            }
+
+            Expr::TaggedTpl(tagged_tpl) if tagged_tpl_operator_enabled => {
+                let opv_with_child_ctx = &mut *self.with_child_ctx();
+                tagged_tpl.visit_mut_with(opv_with_child_ctx);
+
+                let result = TaggedTemplateTransform::to_dd_tagged_tpl_expr(
+                    tagged_tpl,
+                    opv_with_child_ctx.csi_methods,
+                    opv_with_child_ctx.ident_provider,
+                );
+                if result.is_modified() {
+                    expr.map_with_mut(|e| result.expr.unwrap_or(e));
+                    opv_with_child_ctx
+                        .update_status(result.status, Some(TAGGED_TPL_TAG.to_string()));
+                    opv_with_child_ctx.add_site(expr, SiteKind::TaggedTemplate, TAGGED_TPL_TAG);
                 }
             }
 
@@ -133,15 +232,54 @@ impl VisitMut for OperationTransformVisitor<'_> {
                     let result = CallExprTransform::to_dd_call_expr(
                         call,
                         opv_with_child_ctx.csi_methods,
+                        opv_with_child_ctx.symbol_table,
                         opv_with_child_ctx.ident_provider,
                     );
                     if result.is_modified() {
+                        let tag = result.tag.clone().unwrap_or_default();
                         expr.map_with_mut(|e| result.expr.unwrap_or(e));
                         opv_with_child_ctx.update_status(result.status, result.tag);
+                        opv_with_child_ctx.add_site(expr, SiteKind::Method, &tag);
                     }
                 }
             }
 
+            Expr::New(new) => {
+                let opv_with_child_ctx = &mut *self.with_child_ctx();
+                new.visit_mut_children_with(opv_with_child_ctx);
+                let result = NewExprTransform::to_dd_new_expr(
+                    new,
+                    opv_with_child_ctx.csi_methods,
+                    opv_with_child_ctx.symbol_table,
+                    opv_with_child_ctx.ident_provider,
+                );
+                if result.is_modified() {
+                    let tag = result.tag.clone().unwrap_or_default();
+                    expr.map_with_mut(|e| result.expr.unwrap_or(e));
+                    opv_with_child_ctx.update_status(result.status, result.tag);
+                    opv_with_child_ctx.add_site(expr, SiteKind::Constructor, &tag);
+                }
+            }
+
+            Expr::Member(member) if property_read_enabled => {
+                let opv_with_child_ctx = &mut *self.with_child_ctx();
+                member.visit_mut_children_with(opv_with_child_ctx);
+                let result = MemberExprTransform::to_dd_member_expr(
+                    member,
+                    opv_with_child_ctx.csi_methods,
+                    opv_with_child_ctx.ident_provider,
+                );
+                if result.is_modified() {
+                    let tag = result.tag.clone().unwrap_or_default();
+                    expr.map_with_mut(|e| result.expr.unwrap_or(e));
+                    opv_with_child_ctx.update_status(result.status, result.tag);
+                    opv_with_child_ctx.add_site(expr, SiteKind::PropertyRead, &tag);
+                }
+            }
+
+            // a++ updates the member, it is not a read to hook
+            Expr::Update(update) => self.visit_mut_member_children(&mut update.arg),
+
             Expr::OptChain(_) => {
                 let opv_with_child_ctx = &mut *self.with_child_ctx();
                 let transform_result = OptChainTransform::to_dd_cond_expr(
@@ -163,6 +301,9 @@ impl VisitMut for OperationTransformVisitor<'_> {
                 }
             }
 
+            // JSX is instrumented only in jsx mode
+            Expr::JSXElement(_) | Expr::JSXFragment(_) if !self.jsx => {}
+
             Expr::Arrow(arrow) => {
                 let transform_result = ArrowTransform::to_dd_arrow_expr(arrow);
                 if transform_result.is_modified() {
@@ -177,8 +318,38 @@ impl VisitMut for OperationTransformVisitor<'_> {
         }
     }
 
-    fn visit_mut_ident(&mut self, ident: &mut Ident) {
-        self.ident_provider.register_variable(ident);
+    // a.b() is invoked with a as this
+    fn visit_mut_callee(&mut self, callee: &mut Callee) {
+        match callee {
+            Callee::Expr(expr) => self.visit_mut_member_children(expr),
+            _ => callee.visit_mut_children_with(self),
+        }
+    }
+
+    fn visit_mut_tagged_tpl(&mut self, tagged_tpl: &mut TaggedTpl) {
+        self.visit_mut_member_children(&mut tagged_tpl.tag);
+        tagged_tpl.tpl.visit_mut_with(self);
+    }
+
+    fn visit_mut_simple_assign_target(&mut self, target: &mut SimpleAssignTarget) {
+        match target {
+            SimpleAssignTarget::Paren(ParenExpr { expr, .. })
+            | SimpleAssignTarget::TsAs(TsAsExpr { expr, .. })
+            | SimpleAssignTarget::TsSatisfies(TsSatisfiesExpr { expr, .. })
+            | SimpleAssignTarget::TsNonNull(TsNonNullExpr { expr, .. })
+            | SimpleAssignTarget::TsTypeAssertion(TsTypeAssertion { expr, .. }) => {
+                self.visit_mut_member_children(expr)
+            }
+            _ => target.visit_mut_children_with(self),
+        }
+    }
+
+    // destructuring targets: [a.b] = c
+    fn visit_mut_pat(&mut self, pat: &mut Pat) {
+        match pat {
+            Pat::Expr(expr) => self.visit_mut_member_children(expr),
+            _ => pat.visit_mut_children_with(self),
+        }
     }
 
     fn visit_mut_if_stmt(&mut self, if_stmt: &mut IfStmt) {
@@ -188,4 +359,25 @@ impl VisitMut for OperationTransformVisitor<'_> {
 
     // cancel visit child blocks
     fn visit_mut_block_stmt(&mut self, _n: &mut BlockStmt) {}
+
+    // parameters and class field initializers are transformed by BlockTransformVisitor with their own temporal vars
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        function.decorators.visit_mut_with(self);
+    }
+
+    fn visit_mut_constructor(&mut self, _n: &mut Constructor) {}
+
+    fn visit_mut_class_prop(&mut self, prop: &mut ClassProp) {
+        prop.key.visit_mut_with(self);
+        prop.decorators.visit_mut_with(self);
+    }
+
+    fn visit_mut_private_prop(&mut self, prop: &mut PrivateProp) {
+        prop.decorators.visit_mut_with(self);
+    }
+
+    fn visit_mut_auto_accessor(&mut self, accessor: &mut AutoAccessor) {
+        accessor.key.visit_mut_with(self);
+        accessor.decorators.visit_mut_with(self);
+    }
 }
@@ -0,0 +1,259 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+use log::debug;
+use serde::Serialize;
+use std::collections::HashMap;
+use swc::{sourcemap::SourceMap, Compiler};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::transform::transform_status::{Site, SiteKind};
+
+use super::{
+    location_visitor::get_original_location, visitor_util::is_dd_global_method_invocation,
+};
+
+const ANONYMOUS: &str = "<anonymous>";
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SiteInfo {
+    pub id: usize,
+    pub kind: String,
+    pub tag: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub function: Option<String>,
+}
+
+/// Finds the name of the function enclosing every generated hook call with a site id
+#[derive(Default)]
+pub struct SiteVisitor {
+    functions: Vec<String>,
+    classes: Vec<Option<String>>,
+    // name given by a declarator, assignment or property to the function visited next
+    pending_name: Option<String>,
+    site_functions: HashMap<usize, String>,
+}
+
+impl SiteVisitor {
+    fn visit_named<N: VisitWith<Self>>(&mut self, name: String, node: &N) {
+        self.functions.push(name);
+        node.visit_children_with(self);
+        self.functions.pop();
+    }
+
+    fn visit_with_pending_name<N: VisitWith<Self>>(&mut self, name: Option<String>, node: &N) {
+        self.pending_name = name;
+        node.visit_with(self);
+        self.pending_name = None;
+    }
+
+    fn take_name(&mut self, ident: &Option<Ident>) -> String {
+        let pending_name = self.pending_name.take();
+        ident
+            .as_ref()
+            .map(|ident| ident.sym.to_string())
+            .or(pending_name)
+            .unwrap_or_else(|| ANONYMOUS.to_string())
+    }
+
+    fn method_name(&self, key: &PropName) -> String {
+        let name = prop_name(key).unwrap_or_else(|| ANONYMOUS.to_string());
+        match self.classes.last() {
+            Some(Some(class_name)) => format!("{class_name}.{name}"),
+            _ => name,
+        }
+    }
+
+    fn get_site_id(call: &CallExpr) -> Option<usize> {
+        let member = call
+            .callee
+            .as_expr()
+            .and_then(|callee| callee.as_member())?;
+        if !is_dd_global_method_invocation(member) {
+            return None;
+        }
+
+        match call.args.first().map(|arg| &*arg.expr) {
+            Some(Expr::Lit(Lit::Num(number))) => Some(number.value as usize),
+            _ => None,
+        }
+    }
+}
+
+impl Visit for SiteVisitor {
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Some(id) = SiteVisitor::get_site_id(call) {
+            if let Some(function) = self.functions.last() {
+                self.site_functions.insert(id, function.clone());
+            }
+        }
+        call.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.visit_named(fn_decl.ident.sym.to_string(), &*fn_decl.function);
+    }
+
+    fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+        let name = self.take_name(&fn_expr.ident);
+        self.visit_named(name, &*fn_expr.function);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        // the arrow declaring the temporal vars of a parameter or a class field is not a function
+        // of the original code
+        if arrow.span.is_dummy() {
+            return arrow.visit_children_with(self);
+        }
+
+        let name = self.take_name(&None);
+        self.visit_named(name, arrow);
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.classes.push(Some(class_decl.ident.sym.to_string()));
+        class_decl.class.visit_with(self);
+        self.classes.pop();
+    }
+
+    fn visit_class_expr(&mut self, class_expr: &ClassExpr) {
+        let pending_name = self.pending_name.take();
+        let name = class_expr
+            .ident
+            .as_ref()
+            .map(|ident| ident.sym.to_string())
+            .or(pending_name);
+        self.classes.push(name);
+        class_expr.class.visit_with(self);
+        self.classes.pop();
+    }
+
+    fn visit_class_method(&mut self, method: &ClassMethod) {
+        let name = self.method_name(&method.key);
+        self.visit_named(name, &*method.function);
+    }
+
+    fn visit_constructor(&mut self, constructor: &Constructor) {
+        let name = self.method_name(&constructor.key);
+        self.visit_named(name, constructor);
+    }
+
+    fn visit_class_prop(&mut self, prop: &ClassProp) {
+        prop.key.visit_with(self);
+        let name = Some(self.method_name(&prop.key));
+        self.visit_with_pending_name(
+            name.filter(|_| is_function(prop.value.as_deref())),
+            &prop.value,
+        );
+    }
+
+    fn visit_method_prop(&mut self, method: &MethodProp) {
+        let name = prop_name(&method.key).unwrap_or_else(|| ANONYMOUS.to_string());
+        self.visit_named(name, &*method.function);
+    }
+
+    fn visit_key_value_prop(&mut self, prop: &KeyValueProp) {
+        prop.key.visit_with(self);
+        let name = prop_name(&prop.key).filter(|_| is_function(Some(&prop.value)));
+        self.visit_with_pending_name(name, &prop.value);
+    }
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        declarator.name.visit_with(self);
+        let name = declarator
+            .name
+            .as_ident()
+            .map(|ident| ident.id.sym.to_string())
+            .filter(|_| is_function(declarator.init.as_deref()));
+        self.visit_with_pending_name(name, &declarator.init);
+    }
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        assign.left.visit_with(self);
+        let name = match &assign.left {
+            AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) => {
+                Some(ident.id.sym.to_string())
+            }
+            AssignTarget::Simple(SimpleAssignTarget::Member(member)) => match &member.prop {
+                MemberProp::Ident(prop) => Some(prop.sym.to_string()),
+                _ => None,
+            },
+            _ => None,
+        };
+        self.visit_with_pending_name(
+            name.filter(|_| is_function(Some(&assign.right))),
+            &assign.right,
+        );
+    }
+}
+
+fn is_function(expr: Option<&Expr>) -> bool {
+    match expr {
+        Some(Expr::Fn(_) | Expr::Arrow(_) | Expr::Class(_)) => true,
+        Some(Expr::Paren(paren)) => is_function(Some(&paren.expr)),
+        _ => false,
+    }
+}
+
+fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(str) => Some(str.value.to_string()),
+        PropName::Num(num) => Some(num.value.to_string()),
+        _ => None,
+    }
+}
+
+fn kind_name(kind: SiteKind) -> String {
+    match kind {
+        SiteKind::Operator => "operator",
+        SiteKind::Template => "template",
+        SiteKind::TaggedTemplate => "taggedTemplate",
+        SiteKind::PropertyWrite => "propertyWrite",
+        SiteKind::Method => "method",
+        SiteKind::Constructor => "constructor",
+        SiteKind::PropertyRead => "propertyRead",
+    }
+    .to_string()
+}
+
+pub fn get_sites(
+    sites_enabled: bool,
+    file: &str,
+    program: &Program,
+    sites: &[Site],
+    original_source_map: Option<&SourceMap>,
+    compiler: &Compiler,
+) -> Option<Vec<SiteInfo>> {
+    if sites_enabled {
+        debug!("Building site manifest");
+
+        let mut site_visitor = SiteVisitor::default();
+        program.visit_with(&mut site_visitor);
+
+        Some(
+            sites
+                .iter()
+                .map(|site| {
+                    let location = (!site.span.is_dummy()).then(|| {
+                        get_original_location(&site.span, file, original_source_map, compiler)
+                    });
+                    SiteInfo {
+                        id: site.id,
+                        kind: kind_name(site.kind),
+                        tag: site.tag.clone(),
+                        line: location.as_ref().map(|(_, line, _)| *line),
+                        column: location.as_ref().map(|(_, _, column)| *column),
+                        function: site_visitor.site_functions.get(&site.id).cloned(),
+                    }
+                })
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
@@ -0,0 +1,480 @@
+/**
+* Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+* This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+**/
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use swc::atoms::JsWord;
+use swc_common::DUMMY_SP;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::transform::function_prototype_transform::PROTOTYPE;
+
+const REQUIRE: &str = "require";
+const BIND: &str = "bind";
+const NODE_PREFIX: &str = "node:";
+
+// aliases deeper than this are not resolved
+const MAX_ALIAS_DEPTH: usize = 16;
+
+/// A module or a property path of its exports: `require('fs')` or `require('fs').promises.readFile`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct ModuleRef {
+    pub module: String,
+    pub path: Vec<String>,
+}
+
+impl ModuleRef {
+    /// Module name when the reference is the whole module
+    pub fn namespace(&self) -> Option<&str> {
+        self.path.is_empty().then_some(self.module.as_str())
+    }
+
+    /// Module and export names when the reference is a direct export of the module
+    pub fn export(&self) -> Option<(&str, &str)> {
+        match self.path.as_slice() {
+            [export] => Some((self.module.as_str(), export.as_str())),
+            _ => None,
+        }
+    }
+}
+
+/// A method of a global class prototype referenced from a binding: `const { concat } = String.prototype`
+/// or `const sub = String.prototype.substring.bind(s)`
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PrototypeMethod {
+    pub class: JsWord,
+    pub method: String,
+    // receiver given to `bind`, it can be read wherever the bound method is invoked
+    pub bound_this: Option<Ident>,
+}
+
+// syntactic value written to a binding, resolved when the symbol table is queried
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Value {
+    Module(String),
+    Ident(Id),
+    Member(Box<Value>, String),
+    // f.bind(this) with a single identifier argument
+    Bound(Box<Value>, Id),
+}
+
+// module or global identifier a member path starts from
+enum Root {
+    Module(String),
+    Global(JsWord),
+}
+
+impl Value {
+    fn from_expr(expr: &Expr) -> Option<Value> {
+        match expr {
+            Expr::Ident(ident) => Some(Value::Ident(ident.to_id())),
+            Expr::Paren(paren) => Value::from_expr(&paren.expr),
+            Expr::Member(member) => {
+                let prop = match &member.prop {
+                    MemberProp::Ident(prop) => prop.sym.to_string(),
+                    MemberProp::Computed(ComputedPropName { expr, .. }) => match &**expr {
+                        Expr::Lit(Lit::Str(str)) => str.value.to_string(),
+                        _ => return None,
+                    },
+                    MemberProp::PrivateName(_) => return None,
+                };
+                Value::from_expr(&member.obj).map(|obj| obj.member(prop))
+            }
+            Expr::Call(call) => match get_bind_call(call) {
+                Some((target, this)) => Value::from_expr(target)
+                    .map(|target| Value::Bound(Box::new(target), this.to_id())),
+                None => get_required_module(expr).map(|module| Value::Module(module.to_string())),
+            },
+            _ => get_required_module(expr).map(|module| Value::Module(module.to_string())),
+        }
+    }
+
+    fn member(self, prop: String) -> Value {
+        Value::Member(Box::new(self), prop)
+    }
+}
+
+/// Per file symbol table resolving the identifiers bound to a required or imported module or to
+/// one of its exports: `const x = require('m')`, `import * as x from 'm'`, `import { a as b } from 'm'`,
+/// destructuring, aliases and simple re-assignments. Prototype methods of global classes stored
+/// in a binding or bound to a receiver are resolved as well.
+///
+/// Identifiers are keyed by their resolved syntax context when the program has been visited by
+/// the swc resolver, otherwise by their name. A binding written with different values is not
+/// resolved.
+#[derive(Default)]
+pub struct SymbolTable {
+    // None when the binding is written with an unknown or more than one value
+    bindings: HashMap<Id, Option<Value>>,
+    // number of declarations of every name in the file
+    declarations: HashMap<JsWord, usize>,
+    block_scoped: HashSet<JsWord>,
+    consts: HashSet<JsWord>,
+    // kind of the variable declaration whose names are being visited
+    decl_kind: Option<VarDeclKind>,
+}
+
+impl SymbolTable {
+    pub fn collect(program: &Program) -> Self {
+        let mut symbol_table = SymbolTable::default();
+        program.visit_with(&mut symbol_table);
+        symbol_table
+    }
+
+    /// Module export an identifier, a member or a `require` call refers to
+    pub fn resolve(&self, expr: &Expr) -> Option<ModuleRef> {
+        let value = Value::from_expr(expr)?;
+        match self.resolve_path(&value, 0)? {
+            (Root::Module(module), path) => Some(ModuleRef { module, path }),
+            (Root::Global(_), _) => None,
+        }
+    }
+
+    /// Prototype method an identifier refers to. A stored method is only resolved when it and its
+    /// aliases are declared once, so a parameter or a nested variable can not shadow them, and a
+    /// bound method when its receiver is a `const` which cannot be shadowed where it is invoked
+    pub fn resolve_prototype_method(&self, expr: &Expr) -> Option<PrototypeMethod> {
+        let value = Value::from_expr(expr)?;
+        match self.resolve_bound(&value, 0) {
+            Some((target, this)) => {
+                let (class, method) = self.resolve_prototype_path(target)?;
+                let stable_this = self.consts.contains(&this.0) && self.is_declared_once(&this.0);
+                let global_class = !self.declarations.contains_key(&class);
+                (stable_this && global_class).then(|| PrototypeMethod {
+                    class,
+                    method,
+                    bound_this: Some(Ident::new(this.0.clone(), DUMMY_SP, this.1)),
+                })
+            }
+            None => {
+                let (class, method) = self.resolve_prototype_path(&value)?;
+                let global_class = !self.declarations.contains_key(&class);
+                (self.is_declared_once_path(&value, 0) && global_class).then(|| PrototypeMethod {
+                    class,
+                    method,
+                    bound_this: None,
+                })
+            }
+        }
+    }
+
+    // an identifier not written in the file is a global
+    fn resolve_path(&self, value: &Value, depth: usize) -> Option<(Root, Vec<String>)> {
+        if depth > MAX_ALIAS_DEPTH {
+            return None;
+        }
+
+        match value {
+            Value::Module(module) => Some((Root::Module(module.clone()), Vec::new())),
+            Value::Ident(id) => match self.bindings.get(id) {
+                Some(value) => value
+                    .as_ref()
+                    .and_then(|value| self.resolve_path(value, depth + 1)),
+                None => Some((Root::Global(id.0.clone()), Vec::new())),
+            },
+            Value::Member(obj, prop) => {
+                self.resolve_path(obj, depth + 1).map(|(root, mut path)| {
+                    path.push(prop.clone());
+                    (root, path)
+                })
+            }
+            Value::Bound(..) => None,
+        }
+    }
+
+    // Class.prototype.method
+    fn resolve_prototype_path(&self, value: &Value) -> Option<(JsWord, String)> {
+        match self.resolve_path(value, 0)? {
+            (Root::Global(class), path) => match path.as_slice() {
+                [prototype, method] if prototype == PROTOTYPE => Some((class, method.clone())),
+                _ => None,
+            },
+            (Root::Module(_), _) => None,
+        }
+    }
+
+    // target and receiver of a bound method. The bound method and its aliases must be declared
+    // once in a block scope so the receiver is visible wherever they are visible
+    fn resolve_bound<'a>(&'a self, value: &'a Value, depth: usize) -> Option<(&'a Value, &'a Id)> {
+        if depth > MAX_ALIAS_DEPTH {
+            return None;
+        }
+
+        match value {
+            Value::Ident(id)
+                if self.block_scoped.contains(&id.0) && self.is_declared_once(&id.0) =>
+            {
+                let value = self.bindings.get(id)?.as_ref()?;
+                self.resolve_bound(value, depth + 1)
+            }
+            Value::Bound(target, this) => Some((target, this)),
+            _ => None,
+        }
+    }
+
+    // every binding the value is resolved through is declared once
+    fn is_declared_once_path(&self, value: &Value, depth: usize) -> bool {
+        if depth > MAX_ALIAS_DEPTH {
+            return false;
+        }
+
+        match value {
+            Value::Ident(id) => match self.bindings.get(id) {
+                Some(Some(value)) => {
+                    self.is_declared_once(&id.0) && self.is_declared_once_path(value, depth + 1)
+                }
+                Some(None) => false,
+                None => true,
+            },
+            Value::Member(obj, _) => self.is_declared_once_path(obj, depth + 1),
+            Value::Module(_) | Value::Bound(..) => true,
+        }
+    }
+
+    fn is_declared_once(&self, name: &JsWord) -> bool {
+        self.declarations.get(name) == Some(&1)
+    }
+
+    fn declare(&mut self, ident: &Ident) {
+        *self.declarations.entry(ident.sym.clone()).or_default() += 1;
+    }
+
+    fn write(&mut self, ident: &Ident, value: Option<Value>) {
+        match self.bindings.entry(ident.to_id()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+            }
+            Entry::Occupied(mut entry) => {
+                if *entry.get() != value {
+                    entry.insert(None);
+                }
+            }
+        }
+    }
+
+    fn write_pat(&mut self, pat: &Pat, value: Option<Value>) {
+        match pat {
+            Pat::Ident(binding) => self.write(&binding.id, value),
+
+            // const { exec, execFile: run } = require('child_process')
+            Pat::Object(object) => object.props.iter().for_each(|prop| match prop {
+                ObjectPatProp::KeyValue(KeyValuePatProp { key, value: pat }) => {
+                    let prop_value = prop_name(key)
+                        .and_then(|name| value.clone().map(|value| value.member(name)));
+                    self.write_pat(pat, prop_value)
+                }
+                ObjectPatProp::Assign(AssignPatProp {
+                    key,
+                    value: default,
+                    ..
+                }) => {
+                    // a default value may be used instead of the export
+                    let prop_value = value
+                        .clone()
+                        .filter(|_| default.is_none())
+                        .map(|value| value.member(key.sym.to_string()));
+                    self.write(&key.id, prop_value)
+                }
+                ObjectPatProp::Rest(rest) => self.write_pat(&rest.arg, None),
+            }),
+
+            Pat::Array(array) => array
+                .elems
+                .iter()
+                .flatten()
+                .for_each(|elem| self.write_pat(elem, None)),
+            Pat::Rest(rest) => self.write_pat(&rest.arg, None),
+            Pat::Assign(assign) => self.write_pat(&assign.left, None),
+            _ => {}
+        }
+    }
+}
+
+impl Visit for SymbolTable {
+    fn visit_var_decl(&mut self, var: &VarDecl) {
+        self.decl_kind = Some(var.kind);
+        var.visit_children_with(self);
+        self.decl_kind = None;
+    }
+
+    // names in default values or computed keys of a declaration are not declared by it
+    fn visit_expr(&mut self, expr: &Expr) {
+        let decl_kind = self.decl_kind.take();
+        expr.visit_children_with(self);
+        self.decl_kind = decl_kind;
+    }
+
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.declare(&binding.id);
+        match self.decl_kind {
+            Some(VarDeclKind::Const) => {
+                self.block_scoped.insert(binding.id.sym.clone());
+                self.consts.insert(binding.id.sym.clone());
+            }
+            Some(VarDeclKind::Let) => {
+                self.block_scoped.insert(binding.id.sym.clone());
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        self.declare(&fn_decl.ident);
+        fn_decl.visit_children_with(self);
+    }
+
+    fn visit_fn_expr(&mut self, fn_expr: &FnExpr) {
+        if let Some(ident) = &fn_expr.ident {
+            self.declare(ident);
+        }
+        fn_expr.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        self.declare(&class_decl.ident);
+        class_decl.visit_children_with(self);
+    }
+
+    fn visit_class_expr(&mut self, class_expr: &ClassExpr) {
+        if let Some(ident) = &class_expr.ident {
+            self.declare(ident);
+        }
+        class_expr.visit_children_with(self);
+    }
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        if let Some(init) = &declarator.init {
+            self.write_pat(&declarator.name, Value::from_expr(init));
+        }
+        declarator.visit_children_with(self);
+    }
+
+    fn visit_assign_expr(&mut self, assign: &AssignExpr) {
+        let value = match assign.op {
+            AssignOp::Assign => Value::from_expr(&assign.right),
+            _ => None,
+        };
+        match &assign.left {
+            AssignTarget::Simple(SimpleAssignTarget::Ident(binding)) => {
+                self.write(&binding.id, value)
+            }
+            AssignTarget::Pat(pat) => match pat {
+                AssignTargetPat::Object(object) => {
+                    self.write_pat(&Pat::Object(object.clone()), value)
+                }
+                AssignTargetPat::Array(array) => self.write_pat(&Pat::Array(array.clone()), None),
+                AssignTargetPat::Invalid(_) => {}
+            },
+            _ => {}
+        }
+        assign.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, update: &UpdateExpr) {
+        if let Expr::Ident(ident) = &*update.arg {
+            self.write(ident, None);
+        }
+        update.visit_children_with(self);
+    }
+
+    fn visit_for_head(&mut self, head: &ForHead) {
+        match head {
+            ForHead::VarDecl(var) => var
+                .decls
+                .iter()
+                .for_each(|declarator| self.write_pat(&declarator.name, None)),
+            ForHead::Pat(pat) => self.write_pat(pat, None),
+            ForHead::UsingDecl(_) => {}
+        }
+        head.visit_children_with(self);
+    }
+
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        if import.type_only {
+            return;
+        }
+
+        let module = Value::Module(module_name(&import.src.value).to_string());
+        for specifier in import.specifiers.iter() {
+            let local = match specifier {
+                ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+                | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. })
+                | ImportSpecifier::Named(ImportNamedSpecifier { local, .. }) => local,
+            };
+            self.declare(local);
+
+            match specifier {
+                // commonjs modules are imported as default export
+                ImportSpecifier::Default(ImportDefaultSpecifier { local, .. })
+                | ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
+                    self.write(local, Some(module.clone()))
+                }
+                ImportSpecifier::Named(named) if !named.is_type_only => {
+                    let export = match &named.imported {
+                        Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                        Some(ModuleExportName::Str(str)) => str.value.to_string(),
+                        None => named.local.sym.to_string(),
+                    };
+                    self.write(&named.local, Some(module.clone().member(export)))
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Module name without the `node:` scheme of builtin modules
+pub fn module_name(specifier: &str) -> &str {
+    specifier.strip_prefix(NODE_PREFIX).unwrap_or(specifier)
+}
+
+// f.bind(this) with a single identifier argument
+fn get_bind_call(call: &CallExpr) -> Option<(&Expr, &Ident)> {
+    match (&call.callee, call.args.as_slice()) {
+        (
+            Callee::Expr(callee),
+            [ExprOrSpread {
+                spread: None,
+                expr: this,
+            }],
+        ) => match (&**callee, &**this) {
+            (
+                Expr::Member(MemberExpr {
+                    obj,
+                    prop: MemberProp::Ident(prop),
+                    ..
+                }),
+                Expr::Ident(this),
+            ) if prop.sym == BIND => Some((obj, this)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// require('m') with a literal module name
+fn get_required_module(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Call(CallExpr {
+            callee: Callee::Expr(callee),
+            args,
+            ..
+        }) if args.len() == 1 && args[0].spread.is_none() => match (&**callee, &*args[0].expr) {
+            (Expr::Ident(ident), Expr::Lit(Lit::Str(str))) if ident.sym == REQUIRE => {
+                Some(module_name(&str.value))
+            }
+            _ => None,
+        },
+        Expr::Paren(paren) => get_required_module(&paren.expr),
+        _ => None,
+    }
+}
+
+fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(str) => Some(str.value.to_string()),
+        _ => None,
+    }
+}
@@ -10,6 +10,8 @@ const DATADOG_VAR_PREFIX: &str = "__datadog";
 const DD_GLOBAL_NAMESPACE: &str = "_ddiast";
 pub const DD_PLUS_OPERATOR: &str = "plusOperator";
 pub const DD_TEMPLATE_LITERAL_OPERATOR: &str = "tplOperator";
+pub const DD_PROPERTY_WRITE_OPERATOR: &str = "propertyWriteOperator";
+pub const DD_TAGGED_TEMPLATE_OPERATOR: &str = "taggedTplOperator";
 
 pub fn get_dd_local_variable_name(n: usize, prefix: &String) -> String {
     format!("{}{}", get_dd_local_variable_prefix(prefix), n)
@@ -32,6 +34,39 @@ pub fn dd_global_method_invocation(method_name: &str, span: &Span) -> Callee {
     })))
 }
 
+pub fn is_dd_global_method_invocation(member: &MemberExpr) -> bool {
+    matches!(&*member.obj, Expr::Ident(ident) if ident.sym == DD_GLOBAL_NAMESPACE)
+}
+
+// the hook call generated for an operation: the expression itself or the last one of the
+// parenthesized sequence, also as the right side of a rewritten assignment or of the short-circuit
+// of a logical assignment. The sequence of a before hook ends with the original call so its last
+// hook is the `__datadog_x = _ddiast.<dst>Before()` assignation
+pub fn get_dd_hook_call_mut(expr: &mut Expr) -> Option<&mut CallExpr> {
+    match expr {
+        Expr::Call(call) => match &call.callee {
+            Callee::Expr(callee) => match &**callee {
+                Expr::Member(member) if is_dd_global_method_invocation(member) => Some(call),
+                _ => None,
+            },
+            _ => None,
+        },
+        Expr::Paren(paren) => get_dd_hook_call_mut(&mut paren.expr),
+        Expr::Seq(seq) => seq
+            .exprs
+            .iter_mut()
+            .rev()
+            .find_map(|expr| get_dd_hook_call_mut(expr)),
+        Expr::Assign(assign) => get_dd_hook_call_mut(&mut assign.right),
+        Expr::Bin(BinExpr {
+            op: BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing,
+            right,
+            ..
+        }) => get_dd_hook_call_mut(right),
+        _ => None,
+    }
+}
+
 pub fn get_dd_call_expr(
     expr: &Expr,
     arguments: &[ExprOrSpread],
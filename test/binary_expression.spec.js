@@ -5,7 +5,7 @@
 /* eslint-disable no-multi-str */
 const { itEach } = require('mocha-it-each')
 
-const { rewriteAndExpectNoTransformation, rewriteAndExpect, rewriteAndExpectError, wrapBlock } = require('./util')
+const { rewriteAndExpectNoTransformation, rewriteAndExpect, wrapBlock } = require('./util')
 
 describe('binary expression', () => {
   it('does not modify sub', () => {
@@ -236,6 +236,28 @@ __datadog_test_2, __datadog_test_1, __datadog_test_2));'
     )
   })
 
+  it('does evaluate the object and the key of a member assignation once', () => {
+    const js = 'getObj()[key()] += c;'
+    rewriteAndExpect(
+      js,
+      `{
+        let __datadog_test_0, __datadog_test_1, __datadog_test_2;
+        (__datadog_test_0 = getObj())[__datadog_test_1 = key()] = (__datadog_test_2 = __datadog_test_0[\
+__datadog_test_1], _ddiast.plusOperator(__datadog_test_2 + c, __datadog_test_2, c));
+    }`
+    )
+  })
+
+  it('does change logical assignation value only', () => {
+    const js = 'a ??= b + c;'
+    rewriteAndExpect(
+      js,
+      `{
+        a ??= _ddiast.plusOperator(b + c, b, c);
+    }`
+    )
+  })
+
   it('does change assignation with conditional value', () => {
     const js = 'a += b ? c : d;'
     rewriteAndExpect(
@@ -311,29 +333,75 @@ __datadog_test_1, __datadog_test_0, __datadog_test_1)))) > 100) {}
     )
   })
 
-  it('does fail rewrite if duplicate variable name is found', () => {
+  it('does skip variable names declared in the same block', () => {
     const js = 'const __datadog_test_0 = 0; const c = a + b();'
-    rewriteAndExpectError(js)
+    rewriteAndExpect(
+      js,
+      `{
+        let __datadog_test_1, __datadog_test_2;
+        const __datadog_test_0 = 0;
+        const c = (__datadog_test_1 = a, __datadog_test_2 = b(), _ddiast.plusOperator(__datadog_test_1 + \
+__datadog_test_2, __datadog_test_1, __datadog_test_2));
+      }`
+    )
   })
 
-  it('does fail rewrite if duplicate variable name is found inside a child function', () => {
+  it('does not skip variable names declared in a parent block', () => {
     const js = 'const __datadog_test_0 = 0; function z(){const c = a + b();}'
-    rewriteAndExpectError(js)
+    rewriteAndExpect(
+      js,
+      `{
+        const __datadog_test_0 = 0;
+        function z() {
+          let __datadog_test_0, __datadog_test_1;
+          const c = (__datadog_test_0 = a, __datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_0 + \
+__datadog_test_1, __datadog_test_0, __datadog_test_1));
+        }
+      }`
+    )
   })
 
-  it('does fail rewrite if duplicate variable name is found in a function parameter', () => {
+  it('does skip variable names declared in a function parameter', () => {
     const js = 'const a = 0; function z(__datadog_test_0){const c = a + b();}'
-    rewriteAndExpectError(js)
+    rewriteAndExpect(
+      js,
+      `{
+        const a = 0;
+        function z(__datadog_test_0) {
+          let __datadog_test_1, __datadog_test_2;
+          const c = (__datadog_test_1 = a, __datadog_test_2 = b(), _ddiast.plusOperator(__datadog_test_1 + \
+__datadog_test_2, __datadog_test_1, __datadog_test_2));
+        }
+      }`
+    )
   })
 
-  it('does not fail rewrite if duplicate variable name is found in a function parameter', () => {
+  it('does skip variable names declared in a child function parameter', () => {
     const js = 'const a = b() + c; function z(__datadog_test_0){const d = a + c;}'
-    rewriteAndExpectError(js)
+    rewriteAndExpect(
+      js,
+      `{
+        let __datadog_test_1;
+        const a = (__datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_1 + c, __datadog_test_1, c));
+        function z(__datadog_test_0) {
+          const d = _ddiast.plusOperator(a + c, a, c);
+        }
+      }`
+    )
   })
 
-  it('does not fail rewrite if duplicate variable name is found inside a child function', () => {
+  it('does skip variable names declared inside a child function', () => {
     const js = 'const a = b() + c; function z(){const __datadog_test_0 = a + c;}'
-    rewriteAndExpectError(js)
+    rewriteAndExpect(
+      js,
+      `{
+        let __datadog_test_1;
+        const a = (__datadog_test_1 = b(), _ddiast.plusOperator(__datadog_test_1 + c, __datadog_test_1, c));
+        function z() {
+          const __datadog_test_0 = _ddiast.plusOperator(a + c, a, c);
+        }
+      }`
+    )
   })
 
   it('does modify add in a "if" clause without block', () => {
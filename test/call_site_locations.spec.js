@@ -0,0 +1,68 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const path = require('path')
+const { expect } = require('chai')
+const { rewriteAst, rewriteWithOpts } = require('./util')
+
+const JS_FILE = path.join(process.cwd(), 'index.spec.js')
+
+describe('call site locations', () => {
+  it('does pass a location token as first argument of the hook calls', () => {
+    const js = `{
+  const a = b + c.substring(1)
+}`
+    const rewritten = rewriteAst(js, { callSiteLocations: true })
+
+    expect(rewritten).to.include('_ddiast.stringSubstring("0:2:17", __datadog_test_1.call(__datadog_test_0, 1)')
+    expect(rewritten).to.include('_ddiast.plusOperator("0:2:13", __datadog_test_2 + __datadog_test_3, \
+__datadog_test_2, __datadog_test_3)')
+  })
+
+  it('does return the location files', () => {
+    const result = rewriteWithOpts('const a = b + c', { callSiteLocations: true })
+
+    expect(result.locationFiles).to.deep.eq([JS_FILE])
+  })
+
+  describe('without tracer', () => {
+    let _ddiast
+    beforeEach(() => {
+      _ddiast = global._ddiast
+      delete global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does keep the results with the prefix noops', () => {
+      const code = `(function (b, c, re) {
+  return [b + c, \`\${b}!\`, re.exec(c)]
+})`
+      const options = {
+        callSiteLocations: true,
+        keepPrefix: true,
+        csiMethods: [
+          { src: 'plusOperator', operator: true },
+          { src: 'tplOperator', operator: true },
+          { src: 'exec', kind: 'before' }
+        ]
+      }
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, options))
+
+      expect(rewrittenFunction('a', 'b', { exec: (x) => x + '?' })).to.deep.eq(['ab', 'a!', 'b?'])
+    })
+  })
+
+  it('does not pass location tokens by default', () => {
+    const result = rewriteWithOpts('const a = b + c')
+
+    expect(result.content).to.include('_ddiast.plusOperator(b + c, b, c);')
+    expect(result.locationFiles).to.not.exist
+  })
+})
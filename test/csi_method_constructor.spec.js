@@ -0,0 +1,63 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst, rewriteAndExpectNoTransformation } = require('./util')
+
+const constructorOptions = {
+  csiMethods: [{ src: 'URL', dst: 'urlConstructor', kind: 'constructor' }]
+}
+
+describe('csi method constructor', () => {
+  it('does rewrite new expressions', () => {
+    const rewritten = rewriteAst('const u = new URL(path, base)', constructorOptions)
+
+    expect(rewritten).to.include('_ddiast.urlConstructor(new URL(__datadog_test_0, __datadog_test_1), URL, \
+__datadog_test_0, __datadog_test_1)')
+  })
+
+  it('does not rewrite calls with the constructor name', () => {
+    rewriteAndExpectNoTransformation('const u = URL(path); const v = a.URL(path)', constructorOptions)
+  })
+
+  describe('Execution', () => {
+    let _ddiast
+
+    beforeEach(() => {
+      _ddiast = global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does pass the constructed instance, the constructor and the arguments to the hook', () => {
+      const calls = []
+      global._ddiast = {
+        urlConstructor (instance, ctor, ...args) {
+          calls.push([instance.href, ctor, ...args])
+          return instance
+        }
+      }
+
+      const code = `(function (getPath) {
+  return new URL(getPath(), 'http://localhost')
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, constructorOptions))
+
+      let pathCalls = 0
+      const url = rewrittenFunction(() => {
+        pathCalls++
+        return '/a'
+      })
+
+      expect(url).to.be.instanceOf(URL)
+      expect(pathCalls).to.be.equal(1)
+      expect(calls).to.deep.eq([['http://localhost/a', URL, '/a', 'http://localhost']])
+    })
+  })
+})
@@ -0,0 +1,84 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst } = require('./util')
+
+const rewriteWithKind = (code, kind) => rewriteAst(code, { csiMethods: [{ src: 'exec', kind }] })
+
+describe('csi method kind', () => {
+  it('does call the before hook instead of the after hook', () => {
+    const rewritten = rewriteWithKind('const a = b.exec(c)', 'before')
+
+    expect(rewritten).to.include('__datadog_test_3 = _ddiast.execBefore(__datadog_test_1, __datadog_test_0, [')
+    expect(rewritten).to.include('__datadog_test_1.apply(__datadog_test_0, __datadog_test_3));')
+    expect(rewritten).to.not.include('_ddiast.exec(')
+  })
+
+  it('does call both hooks when around', () => {
+    const rewritten = rewriteWithKind('const a = b.exec(c)', 'around')
+
+    expect(rewritten).to.include('_ddiast.execBefore(__datadog_test_1, __datadog_test_0, [')
+    expect(rewritten).to.include('_ddiast.exec(__datadog_test_1.apply(__datadog_test_0, __datadog_test_3), \
+__datadog_test_1, __datadog_test_0, ...__datadog_test_3));')
+  })
+
+  it('does reject a kind other than after for operators', () => {
+    expect(() => rewriteAst('a + b', { csiMethods: [{ src: 'plusOperator', operator: true, kind: 'before' }] }))
+      .to.throw("csiMethods[0].kind: operators only support kind 'after'")
+  })
+
+  describe('Execution', () => {
+    let _ddiast
+
+    beforeEach(() => {
+      _ddiast = global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does invoke the original method with the arguments returned by the before hook', () => {
+      const calls = []
+      global._ddiast = {
+        execBefore (fn, self, args) {
+          calls.push(['before', self.name, ...args])
+          return args.map((arg) => arg.toUpperCase())
+        },
+        exec (res, fn, self, ...args) {
+          calls.push(['after', res, ...args])
+          return res
+        }
+      }
+
+      const code = `(function (receiver, getArg) {
+  return receiver.exec(getArg(), 'b')
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteWithKind(code, 'around'))
+
+      let argCalls = 0
+      const receiver = {
+        name: 'receiver',
+        exec (...args) {
+          return args.join('-')
+        }
+      }
+      const result = rewrittenFunction(receiver, () => {
+        argCalls++
+        return 'a'
+      })
+
+      expect(result).to.be.equal('A-B')
+      expect(argCalls).to.be.equal(1)
+      expect(calls).to.deep.eq([
+        ['before', 'receiver', 'a', 'b'],
+        ['after', 'A-B', 'A', 'B']
+      ])
+    })
+  })
+})
@@ -0,0 +1,86 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst, rewriteAndExpectNoTransformation } = require('./util')
+
+const propertyReadOptions = {
+  csiMethods: [
+    { src: 'queryRead', kind: 'propertyRead', receiverPattern: 'req.query' },
+    { src: 'headersRead', kind: 'propertyRead', receiverPattern: 'req.headers' }
+  ]
+}
+
+describe('csi method property read', () => {
+  it('does rewrite property reads of the receiver pattern', () => {
+    const rewritten = rewriteAst('const a = req.query.a', propertyReadOptions)
+
+    expect(rewritten).to.include('(__datadog_test_0 = req.query, _ddiast.queryRead(__datadog_test_0.a, \
+__datadog_test_0, "a"))')
+  })
+
+  it('does rewrite computed property reads', () => {
+    const rewritten = rewriteAst('const a = req.headers[h]', propertyReadOptions)
+
+    expect(rewritten).to.include('_ddiast.headersRead(__datadog_test_0[__datadog_test_1], __datadog_test_0, \
+__datadog_test_1)')
+  })
+
+  it('does not rewrite assignment targets, deletes, destructuring patterns or method calls', () => {
+    rewriteAndExpectNoTransformation(`[req.query.c] = arr
+req.query.a = 1
+delete req.query.b
+req.query.get(a)`, propertyReadOptions)
+  })
+
+  it('does require a receiverPattern', () => {
+    expect(() => rewriteAst('a', { csiMethods: [{ src: 'queryRead', kind: 'propertyRead' }] }))
+      .to.throw("csiMethods[0].receiverPattern: missing required key for kind 'propertyRead'")
+  })
+
+  describe('Execution', () => {
+    let _ddiast
+
+    beforeEach(() => {
+      _ddiast = global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does pass the value, the object and the key to the hook', () => {
+      const calls = []
+      global._ddiast = {
+        queryRead (value, obj, key) {
+          calls.push([value, obj, key])
+          return value
+        },
+        headersRead (value, obj, key) {
+          calls.push([value, obj, key])
+          return value
+        }
+      }
+
+      const code = `(function (req, getKey) {
+  return req.query.a + req.headers[getKey()]
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, propertyReadOptions))
+
+      let keyCalls = 0
+      const req = { query: { a: 'A' }, headers: { h: 'H' } }
+      const result = rewrittenFunction(req, () => {
+        keyCalls++
+        return 'h'
+      })
+
+      expect(result).to.be.equal('AH')
+      expect(keyCalls).to.be.equal(1)
+      expect(calls).to.deep.eq([['A', req.query, 'a'], ['H', req.headers, 'h']])
+    })
+  })
+})
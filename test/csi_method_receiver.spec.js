@@ -0,0 +1,63 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst, rewriteAndExpectNoTransformation } = require('./util')
+
+const receiverOptions = {
+  csiMethods: [
+    { src: 'exec', dst: 'childProcessExec', receiver: 'child_process' },
+    { src: 'join', dst: 'pathJoin', receiver: 'path' }
+  ]
+}
+
+describe('csi method receiver', () => {
+  it('does rewrite methods of a required module', () => {
+    const rewritten = rewriteAst(`const cp = require('child_process')
+cp.exec(cmd)`, receiverOptions)
+
+    expect(rewritten).to.include('_ddiast.childProcessExec(__datadog_test_1.call(__datadog_test_0, __datadog_test_2), \
+__datadog_test_1, __datadog_test_0, __datadog_test_2)')
+  })
+
+  it('does rewrite destructured exports of a required module', () => {
+    const rewritten = rewriteAst(`const { exec } = require('child_process')
+exec(cmd)`, receiverOptions)
+
+    expect(rewritten).to.include('_ddiast.childProcessExec(exec(__datadog_test_0), exec, undefined, __datadog_test_0)')
+  })
+
+  it('does rewrite imported exports', () => {
+    const rewritten = rewriteAst(`import { join } from 'node:path'
+export const p = join(a, b)`, receiverOptions)
+
+    expect(rewritten).to.include('_ddiast.pathJoin(join(__datadog_test_0, __datadog_test_1), join, undefined, \
+__datadog_test_0, __datadog_test_1)')
+  })
+
+  it('does rewrite methods of a module required in a nested scope or aliased', () => {
+    const rewritten = rewriteAst(`function run (cmd) {
+  const cp = require('child_process')
+  const alias = cp
+  return alias.exec(cmd)
+}`, receiverOptions)
+
+    expect(rewritten).to.include('__datadog_test_0 = alias')
+    expect(rewritten).to.include('_ddiast.childProcessExec(')
+  })
+
+  it('does not rewrite methods of a shadowed binding', () => {
+    rewriteAndExpectNoTransformation(`const cp = require('child_process')
+function run (cp, cmd) {
+  return cp.exec(cmd)
+}`, receiverOptions)
+  })
+
+  it('does not rewrite methods of other receivers', () => {
+    rewriteAndExpectNoTransformation(`const a = arr.join(',')
+other.exec(cmd)`, receiverOptions)
+  })
+})
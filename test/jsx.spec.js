@@ -0,0 +1,30 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const path = require('path')
+const { expect } = require('chai')
+const { rewriteAst } = require('./util')
+
+const JSX_FILE = path.join(process.cwd(), 'index.spec.jsx')
+
+describe('jsx', () => {
+  it('does rewrite jsx attributes and children in jsx mode', () => {
+    const js = "const C = (p) => <a href={'/user/' + p.id}>{`Hello ${p.name}`}</a>"
+    const rewritten = rewriteAst(js, { file: JSX_FILE, jsx: true })
+
+    expect(rewritten).to.be.eq(`const C = (p)=>{
+    let __datadog_test_0;
+    return <a href={(__datadog_test_0 = p.id, _ddiast.plusOperator('/user/' + __datadog_test_0, '/user/', \
+__datadog_test_0))}>{(__datadog_test_0 = p.name, \
+_ddiast.tplOperator(\`Hello \${__datadog_test_0}\`, __datadog_test_0))}</a>;
+};`)
+  })
+
+  it('does not parse jsx if jsx mode is not enabled', () => {
+    const js = "const C = (p) => <a href={'/user/' + p.id}></a>"
+    expect(() => rewriteAst(js, { file: JSX_FILE })).to.throw(Error)
+  })
+})
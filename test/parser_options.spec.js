@@ -0,0 +1,97 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const path = require('path')
+const { expect } = require('chai')
+const { Rewriter, rewriteAst, csiMethods } = require('./util')
+
+const CJS_FILE = path.join(process.cwd(), 'index.spec.cjs')
+
+const DECORATED_CLASS = `@Injectable()
+class Service {
+  concat (a, b) {
+    return a + b
+  }
+}`
+
+describe('parser options', () => {
+  it('does not parse decorators by default', () => {
+    expect(() => rewriteAst(DECORATED_CLASS)).to.throw(Error).with.property('code', 'ParseError')
+  })
+
+  it('does parse legacy decorators', () => {
+    const rewritten = rewriteAst(DECORATED_CLASS, { parserOptions: { decorators: true } })
+
+    expect(rewritten).to.include('return _ddiast.plusOperator(a + b, a, b);')
+  })
+
+  it('does parse decorators before export', () => {
+    const js = `@Controller()
+export class Api {
+  path (a) {
+    return '/' + a
+  }
+}`
+    const rewritten = rewriteAst(js, { parserOptions: { decorators: true, decoratorsBeforeExport: true } })
+
+    expect(rewritten).to.include("return _ddiast.plusOperator('/' + a, '/', a);")
+  })
+
+  it('does allow top level returns in commonjs files', () => {
+    const js = 'if (!a) return\nmodule.exports = a + b'
+    const parserOptions = { isModule: 'script' }
+
+    expect(() => rewriteAst(js, { file: CJS_FILE, parserOptions })).to.throw(Error)
+
+    parserOptions.isModule = 'commonjs'
+    expect(rewriteAst(js, { file: CJS_FILE, parserOptions })).to.include(
+      'module.exports = _ddiast.plusOperator(a + b, a, b);'
+    )
+  })
+
+  it('does parse as module when forced', () => {
+    const js = 'with (obj) { a + b }'
+
+    expect(() => rewriteAst(js)).to.not.throw()
+    expect(() => rewriteAst(js, { parserOptions: { isModule: 'module' } })).to.throw(Error)
+  })
+
+  it('does override parser options per rewrite call', () => {
+    const rewriter = new Rewriter({ localVarPrefix: 'test', csiMethods })
+
+    expect(() => rewriteAst(DECORATED_CLASS, { rewriter })).to.throw(Error)
+
+    const rewritten = rewriteAst(DECORATED_CLASS, { rewriter, rewriteOptions: { parserOptions: { decorators: true } } })
+    expect(rewritten).to.include('return _ddiast.plusOperator(a + b, a, b);')
+
+    expect(() => rewriteAst(DECORATED_CLASS, { rewriter })).to.throw(Error)
+  })
+
+  it('does throw on invalid parser options', () => {
+    expect(() => new Rewriter({ parserOptions: { isModule: 'esm', target: 'es2030' } }))
+      .to.throw(Error)
+      .with.property('errors')
+      .that.deep.equals([
+        {
+          key: 'parserOptions.isModule',
+          message: "unknown module kind 'esm', expected one of auto, module, script, commonjs"
+        },
+        { key: 'parserOptions.target', message: "unknown target 'es2030'" }
+      ])
+  })
+
+  it('does throw on invalid rewrite options', () => {
+    const rewriter = new Rewriter({ localVarPrefix: 'test', csiMethods })
+
+    expect(() => rewriter.rewrite('a + b', 'test.js', { parserOptions: { decorators: 'yes' }, jsx: 1 }))
+      .to.throw(Error, 'Invalid rewrite options')
+      .with.property('errors')
+      .that.deep.equals([
+        { key: 'jsx', message: 'unknown key' },
+        { key: 'parserOptions.decorators', message: 'expected a boolean' }
+      ])
+  })
+})
@@ -0,0 +1,100 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst, rewriteAndExpectNoTransformation } = require('./util')
+
+const propertyWriteOptions = {
+  csiMethods: [{ src: 'propertyWriteOperator', operator: true }]
+}
+
+describe('property write operator', () => {
+  it('does rewrite computed member assignments', () => {
+    const rewritten = rewriteAst('obj[a][b] = value', propertyWriteOptions)
+
+    expect(rewritten).to.include('(__datadog_test_0 = obj[a], __datadog_test_1 = b, __datadog_test_2 = value, \
+_ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = __datadog_test_2, __datadog_test_0, \
+__datadog_test_1, __datadog_test_2));')
+  })
+
+  it('does not rewrite literal keys or compound assignments', () => {
+    rewriteAndExpectNoTransformation("obj.a = v; obj['b'] = v; obj[c] += v", propertyWriteOptions)
+  })
+
+  it('does rewrite logical assignments hooking only the assignment', () => {
+    const rewritten = rewriteAst('obj[key] ??= value', propertyWriteOptions)
+
+    expect(rewritten).to.include('__datadog_test_0[__datadog_test_1] ?? (__datadog_test_2 = value, \
+_ddiast.propertyWriteOperator(__datadog_test_0[__datadog_test_1] = __datadog_test_2, __datadog_test_0, \
+__datadog_test_1, __datadog_test_2))')
+  })
+
+  describe('Execution', () => {
+    let _ddiast
+
+    beforeEach(() => {
+      _ddiast = global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does evaluate the object, the key and the value once', () => {
+      const calls = []
+      global._ddiast = {
+        propertyWriteOperator (res, obj, key, value) {
+          calls.push([obj, key, value])
+          return res
+        }
+      }
+
+      const code = `(function (getObj, getKey, getValue) {
+  return getObj()[getKey()] = getValue()
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, propertyWriteOptions))
+
+      const evaluations = []
+      const obj = {}
+      const result = rewrittenFunction(
+        () => evaluations.push('obj') && obj,
+        () => evaluations.push('key') && '__proto__',
+        () => evaluations.push('value') && 'polluted'
+      )
+
+      expect(result).to.be.equal('polluted')
+      expect(evaluations).to.deep.eq(['obj', 'key', 'value'])
+      expect(calls).to.deep.eq([[obj, '__proto__', 'polluted']])
+    })
+
+    it('does not evaluate the value nor call the hook when a logical assignment short-circuits', () => {
+      const calls = []
+      global._ddiast = {
+        propertyWriteOperator (res, obj, key, value) {
+          calls.push([key, value])
+          return res
+        }
+      }
+
+      const code = `(function (obj, keys, getValue) {
+  obj.a ??= getValue('a')
+  obj[keys[0]] ??= getValue('b')
+  obj[keys[1]] ||= getValue('c')
+  return obj
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, propertyWriteOptions))
+
+      const values = []
+      const result = rewrittenFunction({ c: '' }, ['a', 'c'], (value) => values.push(value) && value)
+
+      expect(result).to.deep.eq({ a: 'a', c: 'c' })
+      expect(values).to.deep.eq(['a', 'c'])
+      expect(calls).to.deep.eq([['c', 'c']])
+    })
+  })
+})
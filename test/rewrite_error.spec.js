@@ -0,0 +1,31 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+
+const path = require('path')
+const { expect } = require('chai')
+const { rewriteWithOpts } = require('./util')
+
+const FILE_PATH = path.join(process.cwd(), 'index.spec.js')
+
+describe('rewrite error', () => {
+  it('does throw a ParseError with position', () => {
+    const js = 'const a = 1;\nconst b = a +;'
+
+    let error
+    try {
+      rewriteWithOpts(js)
+    } catch (e) {
+      error = e
+    }
+
+    expect(error).to.be.instanceOf(Error)
+    expect(error.code).to.be.eq('ParseError')
+    expect(error.file).to.be.eq(FILE_PATH)
+    expect(error.line).to.be.eq(2)
+    expect(error.column).to.be.eq(13)
+    expect(error.message).to.be.eq(`Error parsing ${FILE_PATH}:2:13: Expression expected`)
+    expect(error.diagnostic).to.be.eq('Expression expected')
+  })
+})
@@ -0,0 +1,55 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { Rewriter, rewriteAst, rewriteWithOpts, csiMethods } = require('./util')
+
+describe('rewrite options', () => {
+  let rewriter
+
+  beforeEach(() => {
+    rewriter = new Rewriter({ localVarPrefix: 'test', csiMethods, literals: false, telemetryVerbosity: 'DEBUG' })
+  })
+
+  it('does rewrite only the csi methods subset', () => {
+    const js = 'const a = b + c.substring(1)'
+
+    const rewritten = rewriteAst(js, { rewriter, rewriteOptions: { csiMethods: ['substring'] } })
+    expect(rewritten).to.include('_ddiast.stringSubstring(')
+    expect(rewritten).to.not.include('_ddiast.plusOperator(')
+  })
+
+  it('does keep the base config for the following files', () => {
+    const js = 'const a = b + c.substring(1)'
+
+    rewriteAst(js, { rewriter, rewriteOptions: { csiMethods: [] } })
+
+    const rewritten = rewriteAst(js, { rewriter })
+    expect(rewritten).to.include('_ddiast.stringSubstring(')
+    expect(rewritten).to.include('_ddiast.plusOperator(')
+  })
+
+  it('does extract literals when enabled for one file', () => {
+    const js = "const a = 'literal' + b"
+
+    expect(rewriteWithOpts(js, { rewriter }).literalsResult).to.not.exist
+    expect(rewriteWithOpts(js, { rewriter, rewriteOptions: { literals: true } }).literalsResult).to.exist
+  })
+
+  it('does override telemetry verbosity', () => {
+    const js = 'const a = b.substring(1)'
+
+    const { metrics } = rewriteWithOpts(js, { rewriter, rewriteOptions: { telemetryVerbosity: 'OFF' } })
+    expect(metrics.instrumentedPropagation).to.be.eq(0)
+  })
+
+  it('does throw on csi methods not present in the config', () => {
+    expect(() => rewriter.rewrite('a + b', 'test.js', { csiMethods: ['eval'] }))
+      .to.throw(Error, 'Invalid rewrite options')
+      .with.property('errors')
+      .that.deep.equals([{ key: 'csiMethods[0]', message: "unknown csi method 'eval'" }])
+  })
+})
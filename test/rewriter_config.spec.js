@@ -128,14 +128,31 @@ _ddiast.plus("b" + c, "b", c));
       expect(rewriter.csiMethods()).to.include('concat')
     })
 
+    it('should list the before and after hooks', () => {
+      const rewriter = new Rewriter({
+        csiMethods: [
+          { src: 'exec', kind: 'before' },
+          { src: 'execSync', kind: 'around' },
+          { src: 'trimStart', dst: 'trim' },
+          { src: 'trimEnd', dst: 'trim' }
+        ]
+      })
+
+      expect(rewriter.csiMethods()).to.deep.eq(['execBefore', 'execSyncBefore', 'execSync', 'trim'])
+    })
+
     it('should not throw Error with no RewriterConfig', () => {
       const rewriter = new Rewriter()
 
       expect(rewriter.csiMethods()).to.be.empty
     })
 
-    it('should not throw Error', () => {
-      const rewriter = new Rewriter([1, 2])
+    it('should throw Error with an invalid RewriterConfig', () => {
+      expect(() => new Rewriter([1, 2])).to.throw(Error, /Invalid rewriter config/)
+    })
+
+    it('should not throw Error with an invalid RewriterConfig in lenient mode', () => {
+      const rewriter = new Rewriter({ csiMethods: [1, 2], lenient: true })
       expect(rewriter.csiMethods()).to.be.empty
     })
   })
@@ -194,8 +211,15 @@ _ddiast.plus("b" + c, "b", c));
       expect(metrics.propagationDebug.get('+')).eq(1)
     })
 
-    it('should accept unknown verbosity and set it as INFORMATION', () => {
-      const rewriter = new Rewriter({ csiMethods, telemetryVerbosity: 'unknown' })
+    it('should not accept unknown verbosity', () => {
+      expect(() => new Rewriter({ csiMethods, telemetryVerbosity: 'unknown' })).to.throw(
+        Error,
+        /telemetryVerbosity: unknown telemetry verbosity 'unknown'/
+      )
+    })
+
+    it('should accept unknown verbosity and set it as INFORMATION in lenient mode', () => {
+      const rewriter = new Rewriter({ csiMethods, telemetryVerbosity: 'unknown', lenient: true })
       const response = rewriter.rewrite('{const a = b + c}', 'index.js')
       expect(response).to.have.property('content')
       expect(response).to.have.property('metrics')
@@ -220,6 +244,53 @@ _ddiast.plus("b" + c, "b", c));
     })
   })
 
+  describe('validation', () => {
+    const getConfigErrors = (config) => {
+      try {
+        // eslint-disable-next-line no-new
+        new Rewriter(config)
+      } catch (e) {
+        return e.errors
+      }
+    }
+
+    it('should report unknown keys', () => {
+      const errors = getConfigErrors({ csiMethods, unknownKey: true })
+      expect(errors).to.deep.eq([{ key: 'unknownKey', message: 'unknown key' }])
+    })
+
+    it('should report wrong types', () => {
+      const errors = getConfigErrors({ comments: 'yes', csiMethods: [{ src: 'trim', operator: 1 }] })
+      expect(errors).to.deep.eq([
+        { key: 'comments', message: 'expected a boolean' },
+        { key: 'csiMethods[0].operator', message: 'expected a boolean' }
+      ])
+    })
+
+    it('should report unknown operators', () => {
+      const errors = getConfigErrors({ csiMethods: [{ src: 'minusOperator', operator: true }] })
+      expect(errors).to.deep.eq([
+        {
+          key: 'csiMethods[0].src',
+          message: "unknown operator 'minusOperator', expected one of plusOperator, tplOperator, \
+propertyWriteOperator, taggedTplOperator"
+        }
+      ])
+    })
+
+    it('should report duplicate src entries', () => {
+      const errors = getConfigErrors({ csiMethods: [{ src: 'trim' }, { src: 'trim', dst: 'stringTrim' }] })
+      expect(errors).to.deep.eq([{ key: 'csiMethods[1]', message: "duplicate src 'trim'" }])
+    })
+
+    it('should report invalid localVarPrefix characters', () => {
+      const errors = getConfigErrors({ localVarPrefix: 'test-prefix' })
+      expect(errors).to.deep.eq([
+        { key: 'localVarPrefix', message: 'expected a non empty string of [A-Za-z0-9_$] characters' }
+      ])
+    })
+  })
+
   describe('dummy rewriter', () => {
     describe('rewrite method', () => {
       it('should have same return type as Rewriter.rewrite', () => {
@@ -0,0 +1,72 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst, rewriteWithOpts } = require('./util')
+
+describe('site ids', () => {
+  it('does pass the site id as first argument of the hook calls', () => {
+    const js = `function f (a, b) {
+  return a + b.substring(1)
+}`
+    const result = rewriteWithOpts(js, { siteIds: true })
+
+    expect(result.content).to.include('_ddiast.stringSubstring(0, __datadog_test_1.call(__datadog_test_0, 1)')
+    expect(result.content).to.include('_ddiast.plusOperator(1, __datadog_test_2 + __datadog_test_3, \
+__datadog_test_2, __datadog_test_3)')
+  })
+
+  it('does return the site manifest', () => {
+    const js = `function f (a, b) {
+  return a + b.substring(1)
+}`
+    const { sites } = rewriteWithOpts(js, { siteIds: true })
+
+    expect(sites).to.deep.eq([
+      { id: 0, kind: 'method', tag: 'substring', line: 2, column: 14, function: 'f' },
+      { id: 1, kind: 'operator', tag: '+', line: 2, column: 10, function: 'f' }
+    ])
+  })
+
+  describe('without tracer', () => {
+    let _ddiast
+    beforeEach(() => {
+      _ddiast = global._ddiast
+      delete global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does keep the results with the prefix noops', () => {
+      const code = `(function (b, c, re) {
+  return [b + c, \`\${b}!\`, re.exec(c)]
+})`
+      const options = {
+        siteIds: true,
+        callSiteLocations: true,
+        keepPrefix: true,
+        csiMethods: [
+          { src: 'plusOperator', operator: true },
+          { src: 'tplOperator', operator: true },
+          { src: 'exec', kind: 'before' }
+        ]
+      }
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, options))
+
+      expect(rewrittenFunction('a', 'b', { exec: (x) => x + '?' })).to.deep.eq(['ab', 'a!', 'b?'])
+    })
+  })
+
+  it('does not return sites by default', () => {
+    const result = rewriteWithOpts('const a = b + c')
+
+    expect(result.content).to.include('_ddiast.plusOperator(b + c, b, c);')
+    expect(result.sites).to.not.exist
+  })
+})
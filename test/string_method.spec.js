@@ -168,6 +168,22 @@ _ddiast.stringSubstring(__datadog_test_1.call(__datadog_test_0, 2), __datadog_te
       rewriteAndExpectNoTransformation(js)
     })
 
+    it('does modify computed substring call with a literal key', () => {
+      const js = 'a[\'substring\'](1);'
+      rewriteAndExpect(
+        js,
+        `{
+  let __datadog_test_0, __datadog_test_1;
+(__datadog_test_0 = a, __datadog_test_1 = __datadog_test_0.substring, _ddiast.stringSubstring(__datadog_test_1\
+.call(__datadog_test_0, 1), __datadog_test_1, __datadog_test_0, 1));\n}`
+      )
+    })
+
+    it('does not modify computed substring call with a non literal key', () => {
+      const js = 'a[method](1);'
+      rewriteAndExpectNoTransformation(js)
+    })
+
     it('does modify member.prop.substring call', () => {
       const js = 'a.b.c.substring(1);'
       rewriteAndExpect(
@@ -271,6 +287,68 @@ _ddiast.concat(__datadog_test_0.apply("hello", [\n"world",\n__datadog_test_1\n])
       )
     })
 
+    describe('transpiled calls', () => {
+      it('does modify Reflect.apply(String.prototype.concat, a, [b])', () => {
+        const builder = fn().args('hello', ' world')
+        const js = builder.build('return Reflect.apply(String.prototype.concat, a, [b])')
+        rewriteAndExpectAndExpectEval(
+          js,
+          builder.build(`let __datadog_test_0, __datadog_test_1, __datadog_test_2;
+        return (__datadog_test_0 = a, __datadog_test_1 = String.prototype.concat, __datadog_test_2 = b, \
+_ddiast.concat(__datadog_test_1.apply(__datadog_test_0, [\n__datadog_test_2\n]), __datadog_test_1, \
+__datadog_test_0, __datadog_test_2));`)
+        )
+      })
+
+      it('does modify (0, a.concat)(b) calling it without receiver', () => {
+        const js = '(0, a.concat)(b);'
+        rewriteAndExpect(
+          js,
+          `{
+  let __datadog_test_0, __datadog_test_1;
+(__datadog_test_0 = a.concat, __datadog_test_1 = b, _ddiast.concat(__datadog_test_0(__datadog_test_1), \
+__datadog_test_0, undefined, __datadog_test_1));\n}`
+        )
+      })
+    })
+
+    describe('prototype method references', () => {
+      it('does modify concat.call(a, b) with concat destructured from String.prototype', () => {
+        const builder = fn().args('hello', ' world')
+        const js = builder.build(`const { concat } = String.prototype
+        return concat.call(a, b)`)
+        rewriteAndExpectAndExpectEval(
+          js,
+          builder.build(`let __datadog_test_0, __datadog_test_1, __datadog_test_2;
+        const { concat } = String.prototype;
+        return (__datadog_test_0 = a, __datadog_test_1 = concat, __datadog_test_2 = b, \
+_ddiast.concat(__datadog_test_1.call(__datadog_test_0, __datadog_test_2), __datadog_test_1, __datadog_test_0, \
+__datadog_test_2));`)
+        )
+      })
+
+      it('does modify sub(1) with sub bound to a const receiver', () => {
+        const builder = fn().args('hello')
+        const js = builder.build(`const s = a
+        const sub = String.prototype.substring.bind(s)
+        return sub(1)`)
+        rewriteAndExpectAndExpectEval(
+          js,
+          builder.build(`const s = a;
+        const sub = String.prototype.substring.bind(s);
+        return _ddiast.stringSubstring(sub(1), String.prototype.substring, s, 1);`)
+        )
+      })
+
+      it('does not modify sub(1) with sub bound to a parameter', () => {
+        const js = `function f (s) {
+  const sub = String.prototype.substring.bind(s)
+  return sub(1)
+}`
+        rewriteAndExpectNoTransformation(js)
+      })
+    })
+
     describe('spread arguments', () => {
       it('does modify String.prototype.concat.call(...a)', () => {
         const builder = fn().args(['heLLo', ' ', 'world'])
@@ -0,0 +1,93 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const { expect } = require('chai')
+const { rewriteAst, rewriteAndExpectNoTransformation } = require('./util')
+
+const taggedTplOptions = {
+  csiMethods: [{ src: 'taggedTplOperator', operator: true }]
+}
+
+describe('tagged template operator', () => {
+  it('does rewrite tagged templates with substitutions', () => {
+    const rewritten = rewriteAst('const q = sql`select ${a}`', taggedTplOptions)
+
+    expect(rewritten).to.include('(__datadog_test_0 = a, __datadog_test_1 = ((strings)=>strings)`select \
+${__datadog_test_0}`, _ddiast.taggedTplOperator(sql(__datadog_test_1, __datadog_test_0), sql, __datadog_test_1, \
+__datadog_test_0))')
+  })
+
+  it('does not rewrite tagged templates without substitutions', () => {
+    rewriteAndExpectNoTransformation('const q = sql`select 1`', taggedTplOptions)
+  })
+
+  describe('Execution', () => {
+    let _ddiast
+
+    beforeEach(() => {
+      _ddiast = global._ddiast
+    })
+
+    afterEach(() => {
+      global._ddiast = _ddiast
+    })
+
+    it('does pass the tag, the strings and the substitutions to the hook', () => {
+      const calls = []
+      global._ddiast = {
+        taggedTplOperator (res, tag, strings, ...substitutions) {
+          calls.push([res, tag, strings, substitutions])
+          return res
+        }
+      }
+
+      const code = `(function (dir, getFile) {
+  return String.raw\`C:\\\\\${dir}\\\\\${getFile()}\`
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, taggedTplOptions))
+
+      let fileCalls = 0
+      const result = rewrittenFunction('tmp', () => {
+        fileCalls++
+        return 'a.txt'
+      })
+
+      expect(result).to.be.equal('C:\\\\tmp\\\\a.txt')
+      expect(fileCalls).to.be.equal(1)
+      expect(calls).to.have.length(1)
+      expect(calls[0][1]).to.be.equal(String.raw)
+      expect(calls[0][2].raw).to.deep.eq(['C:\\\\', '\\\\', ''])
+      expect(calls[0][3]).to.deep.eq(['tmp', 'a.txt'])
+    })
+
+    it('does pass the same frozen strings object on every evaluation of a call site', () => {
+      global._ddiast = {
+        taggedTplOperator: (res) => res
+      }
+
+      const code = `(function (tag, obj, a) {
+  return [tag\`a\${a}\`, obj.tag\`a\${a}\`]
+})`
+      // eslint-disable-next-line no-eval
+      const rewrittenFunction = (1, eval)(rewriteAst(code, taggedTplOptions))
+
+      const tag = (strings) => strings
+      const obj = {
+        tag (strings) {
+          return this === obj && strings
+        }
+      }
+      const [first, firstMember] = rewrittenFunction(tag, obj, 1)
+      const [second, secondMember] = rewrittenFunction(tag, obj, 2)
+
+      expect(first).to.be.equal(second)
+      expect(firstMember).to.be.equal(secondMember)
+      expect(first).to.not.be.equal(firstMember)
+      expect(Object.isFrozen(first)).to.be.true
+    })
+  })
+})
@@ -81,6 +81,16 @@ const result = (__datadog_test_0 = a, __datadog_test_1 = b, _ddiast.tplOperator(
       )
     })
 
+    it('literal and non literal substitutions', () => {
+      const js = 'const result = `${1}-${a}${" "}`;'
+      rewriteAndExpect(
+        js,
+        '{\nlet __datadog_test_0;\n\
+const result = (__datadog_test_0 = a, _ddiast.tplOperator(`${1}-${__datadog_test_0}${" "}`, 1, __datadog_test_0, \
+" "));\n}'
+      )
+    })
+
     it('end', () => {
       const js = 'const result = `Hello World!${a}`;'
       rewriteAndExpect(
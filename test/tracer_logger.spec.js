@@ -41,7 +41,9 @@ describe('logger', () => {
       'Rewriting js file: test.js with config: \
 Config { chain_source_map: false, print_comments: false, local_var_prefix: "logger-test", csi_methods: \
 CsiMethods { methods: [], plus_operator: None, tpl_operator: None, method_with_literal_callers: [] }, \
-verbosity: Information, literals: true }'
+verbosity: Information, literals: true, syntax: Auto, strip_types: false, jsx: false, \
+parser_options: ParserOptions { decorators: false, decorators_before_export: false, is_module: Auto, target: EsNext }, \
+call_site_locations: false, site_ids: false }'
     ])
   })
 
@@ -58,7 +60,9 @@ verbosity: Information, literals: true }'
       'Rewriting js file: test.js with config: \
 Config { chain_source_map: false, print_comments: false, local_var_prefix: "logger-test", csi_methods: \
 CsiMethods { methods: [], plus_operator: None, tpl_operator: None, method_with_literal_callers: [] }, \
-verbosity: Information, literals: true }'
+verbosity: Information, literals: true, syntax: Auto, strip_types: false, jsx: false, \
+parser_options: ParserOptions { decorators: false, decorators_before_export: false, is_module: Auto, target: EsNext }, \
+call_site_locations: false, site_ids: false }'
     ])
   })
 
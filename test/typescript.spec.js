@@ -0,0 +1,38 @@
+/**
+ * Unless explicitly stated otherwise all files in this repository are licensed under the Apache-2.0 License.
+ * This product includes software developed at Datadog (https://www.datadoghq.com/). Copyright 2022 Datadog, Inc.
+ **/
+/* eslint-disable no-unused-expressions */
+
+const path = require('path')
+const { expect } = require('chai')
+const { rewriteAst } = require('./util')
+
+const TS_FILE = path.join(process.cwd(), 'index.spec.ts')
+
+describe('typescript', () => {
+  it('does rewrite and keep types', () => {
+    const js = 'function f(a: string, b: number): string { return a + b }'
+    const rewritten = rewriteAst(js, { file: TS_FILE })
+
+    expect(rewritten).to.be.eq(`function f(a: string, b: number): string {
+    return _ddiast.plusOperator(a + b, a, b);
+}`)
+  })
+
+  it('does rewrite and strip types', () => {
+    const js = 'function f(a: string, b: number): string { return a + b }'
+    const rewritten = rewriteAst(js, { file: TS_FILE, stripTypes: true })
+
+    expect(rewritten).to.be.eq(`function f(a, b) {
+    return _ddiast.plusOperator(a + b, a, b);
+}`)
+  })
+
+  it('does rewrite a js file as typescript', () => {
+    const js = 'const a: string = b + c'
+    const rewritten = rewriteAst(js, { syntax: 'typescript' })
+
+    expect(rewritten).to.be.eq('const a: string = _ddiast.plusOperator(b + c, b, c);')
+  })
+})
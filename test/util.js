@@ -58,9 +58,10 @@ const rewriteWithOpts = (code, opts) => {
     opts || {}
   )
 
-  const rewriter = opts.rewriter ?? new Rewriter(opts)
-  const file = opts.file ?? path.join(process.cwd(), 'index.spec.js')
-  return rewriter.rewrite(code, file)
+  const { rewriter: optsRewriter, file: optsFile, keepSourceMap, keepPrefix, rewriteOptions, ...config } = opts
+  const rewriter = optsRewriter ?? new Rewriter(config)
+  const file = optsFile ?? path.join(process.cwd(), 'index.spec.js')
+  return rewriter.rewrite(code, file, rewriteOptions)
 }
 
 const rewriteAst = (code, opts) => {
@@ -94,12 +95,6 @@ const rewriteAndExpect = (code, expect, block, opts) => {
   return rewritten
 }
 
-const rewriteAndExpectError = (code) => {
-  expect(() => {
-    rewriteAndExpect(code, code)
-  }).to.throw(Error, /Variable name duplicated/)
-}
-
 const GLOBAL_METHODS_TEMPLATE = `;(function(globals){
   globals._ddiast = globals._ddiast || { __CSI_METHODS__ };
 }((1,eval)('this')));`
@@ -200,7 +195,6 @@ module.exports = {
   rewriteWithOpts,
   rewriteAndExpectNoTransformation,
   rewriteAndExpect,
-  rewriteAndExpectError,
   wrapBlock,
   Rewriter,
   DummyRewriter,